@@ -0,0 +1,372 @@
+//! Compares the pipelined X11 enumeration with the enumeration it replaced, which waited for
+//! every reply before sending the next request.
+//!
+//! RandR monitors can't share an output, and a monitor without one fails the enumeration, so
+//! the X server needs an output per monitor. Xvfb has a single output, the dummy driver has
+//! sixteen:
+//!
+//! ```sh
+//! sudo Xorg :99 -config "$PWD/benches/xorg-dummy.conf" -noreset -nolisten tcp &
+//! DISPLAY=:99 cargo bench --bench x11_enumeration
+//! ```
+//!
+//! For the run, the screen is split into `BENCH_MONITORS` (4 by default) monitors side by side,
+//! each with an output of its own, as `xrandr --setmonitor BENCH-0 1920/0x1080/0+0+0 DUMMY0`
+//! would. They are deleted again when the benchmark ends or panics. As this changes the monitors
+//! of the server, the benchmark refuses to run on a server with outputs other than those of the
+//! dummy driver or Xvfb, unless `BENCH_ALLOW_ANY_DISPLAY` is set.
+//!
+//! The pipelined enumeration also reads the EDIDs, the primary output and the work area, so it
+//! sends more requests than the old one and the speedup is a lower bound. Round trips cost far
+//! more over SSH forwarding than on a local server, so the difference there is larger still.
+
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+mod x11 {
+    use std::{
+        env,
+        time::{Duration, Instant},
+    };
+
+    use display_info::DisplayInfo;
+    use scopeguard::guard;
+    use xcb::{
+        Connection, Extension,
+        randr::{
+            DeleteMonitor, GetOutputInfo, GetScreenResources, MonitorInfoBuf, Output, SetMonitor,
+        },
+        x::{Atom, InternAtom, Window},
+    };
+
+    const ITERATIONS: u32 = 200;
+
+    /// The enumeration before it was pipelined, request for request.
+    mod sequential {
+        use std::str;
+
+        use xcb::{
+            Connection, Xid,
+            randr::{
+                GetCrtcInfo, GetMonitors, GetOutputInfo, GetScreenResources, Mode, ModeFlag,
+                ModeInfo, Output, Rotation,
+            },
+            x::{ATOM_RESOURCE_MANAGER, ATOM_STRING, Atom, GetAtomName, GetProperty, Screen},
+        };
+
+        fn get_name(conn: &Connection, atom: Atom) -> xcb::Result<String> {
+            let get_atom_value = conn.send_request(&GetAtomName { atom });
+
+            let get_atom_value_reply = conn.wait_for_reply(get_atom_value)?;
+            Ok(get_atom_value_reply.name().to_string())
+        }
+
+        fn get_current_frequency(mode_infos: &[ModeInfo], mode: Mode) -> f32 {
+            let mode_info = match mode_infos.iter().find(|m| m.id == mode.resource_id()) {
+                Some(mode_info) => mode_info,
+                None => return 0.0,
+            };
+
+            let vtotal = {
+                let mut val = mode_info.vtotal;
+                if mode_info.mode_flags.contains(ModeFlag::DOUBLE_SCAN) {
+                    val *= 2;
+                }
+                if mode_info.mode_flags.contains(ModeFlag::INTERLACE) {
+                    val /= 2;
+                }
+                val
+            };
+
+            if vtotal != 0 && mode_info.htotal != 0 {
+                (mode_info.dot_clock as f32) / (vtotal as f32 * mode_info.htotal as f32)
+            } else {
+                0.0
+            }
+        }
+
+        fn get_scale_factor(conn: &Connection, screen: &Screen) -> Option<f32> {
+            let xft_dpi_prefix = "Xft.dpi:\t";
+
+            let get_property_cookie = conn.send_request(&GetProperty {
+                delete: false,
+                window: screen.root(),
+                property: ATOM_RESOURCE_MANAGER,
+                r#type: ATOM_STRING,
+                long_offset: 0,
+                long_length: 60,
+            });
+
+            let get_property_reply = conn.wait_for_reply(get_property_cookie).ok()?;
+
+            if get_property_reply.format() != 8 {
+                return None;
+            }
+
+            let resource_manager = str::from_utf8(get_property_reply.value()).ok()?;
+
+            let xft_dpi = resource_manager
+                .split('\n')
+                .find(|s| s.starts_with(xft_dpi_prefix))?
+                .strip_prefix(xft_dpi_prefix)?;
+
+            let dpi = xft_dpi.parse::<f32>().ok()?;
+
+            Some(dpi / 96.0)
+        }
+
+        fn get_rotation_frequency(
+            conn: &Connection,
+            mode_infos: &[ModeInfo],
+            output: &Output,
+        ) -> xcb::Result<(f32, f32)> {
+            let get_output_info_cookie = conn.send_request(&GetOutputInfo {
+                output: *output,
+                config_timestamp: 0,
+            });
+
+            let get_output_info_reply = conn.wait_for_reply(get_output_info_cookie)?;
+
+            let get_crtc_info_cookie = conn.send_request(&GetCrtcInfo {
+                crtc: get_output_info_reply.crtc(),
+                config_timestamp: 0,
+            });
+
+            let get_crtc_info_reply = conn.wait_for_reply(get_crtc_info_cookie)?;
+
+            let mode = get_crtc_info_reply.mode();
+
+            let rotation = match get_crtc_info_reply.rotation() {
+                Rotation::ROTATE_0 => 0.0,
+                Rotation::ROTATE_90 => 90.0,
+                Rotation::ROTATE_180 => 180.0,
+                Rotation::ROTATE_270 => 270.0,
+                _ => 0.0,
+            };
+
+            let frequency = get_current_frequency(mode_infos, mode);
+
+            Ok((rotation, frequency))
+        }
+
+        /// The number of monitors.
+        pub fn get_all(conn: &Connection, screen: &Screen) -> usize {
+            let scale_factor = get_scale_factor(conn, screen).unwrap_or(1.0);
+
+            let get_monitors_cookie = conn.send_request(&GetMonitors {
+                window: screen.root(),
+                get_active: true,
+            });
+
+            let get_monitors_reply = conn.wait_for_reply(get_monitors_cookie).unwrap();
+
+            let monitor_info_iterator = get_monitors_reply.monitors();
+
+            let get_screen_resources_cookie = conn.send_request(&GetScreenResources {
+                window: screen.root(),
+            });
+
+            let get_screen_resources_reply =
+                conn.wait_for_reply(get_screen_resources_cookie).unwrap();
+
+            let mode_infos = get_screen_resources_reply.modes();
+
+            let mut display_infos = Vec::new();
+
+            for monitor_info in monitor_info_iterator {
+                let output = monitor_info.outputs().first().unwrap();
+
+                let (rotation, frequency) =
+                    get_rotation_frequency(conn, mode_infos, output).unwrap_or((0.0, 0.0));
+
+                let name = get_name(conn, monitor_info.name()).unwrap();
+
+                display_infos.push((name, scale_factor, rotation, frequency));
+            }
+
+            display_infos.len()
+        }
+    }
+
+    fn intern_atom(conn: &Connection, name: &[u8]) -> Atom {
+        let cookie = conn.send_request(&InternAtom {
+            only_if_exists: false,
+            name,
+        });
+
+        conn.wait_for_reply(cookie).unwrap().atom()
+    }
+
+    /// The outputs of the screen with their names.
+    fn get_outputs(conn: &Connection, root: Window) -> Vec<(Output, String)> {
+        let cookie = conn.send_request(&GetScreenResources { window: root });
+        let reply = conn.wait_for_reply(cookie).unwrap();
+
+        let cookies = reply
+            .outputs()
+            .iter()
+            .map(|&output| {
+                let cookie = conn.send_request(&GetOutputInfo {
+                    output,
+                    config_timestamp: 0,
+                });
+
+                (output, cookie)
+            })
+            .collect::<Vec<_>>();
+
+        cookies
+            .into_iter()
+            .map(|(output, cookie)| {
+                let reply = conn.wait_for_reply(cookie).unwrap();
+
+                (output, String::from_utf8_lossy(reply.name()).into_owned())
+            })
+            .collect()
+    }
+
+    /// The outputs of the dummy driver are named `DUMMY0` to `DUMMY15`, the one of Xvfb
+    /// `screen`.
+    fn is_virtual_output(name: &str) -> bool {
+        name == "screen"
+            || name
+                .strip_prefix("DUMMY")
+                .is_some_and(|index| index.parse::<u32>().is_ok())
+    }
+
+    /// Split the screen into monitors side by side, one per output.
+    fn add_monitors(
+        conn: &Connection,
+        root: Window,
+        width: u16,
+        height: u16,
+        outputs: &[Output],
+    ) -> Vec<Atom> {
+        let monitor_width = width / outputs.len() as u16;
+
+        outputs
+            .iter()
+            .enumerate()
+            .map(|(index, &output)| {
+                let name = intern_atom(conn, format!("BENCH-{index}").as_bytes());
+                let monitor_info = MonitorInfoBuf::new(
+                    name,
+                    false,
+                    false,
+                    (index as u16 * monitor_width) as i16,
+                    0,
+                    monitor_width,
+                    height,
+                    0,
+                    0,
+                    &[output],
+                );
+
+                let cookie = conn.send_request_checked(&SetMonitor {
+                    window: root,
+                    monitorinfo: &monitor_info,
+                });
+                conn.check_request(cookie).unwrap();
+
+                name
+            })
+            .collect()
+    }
+
+    fn delete_monitors(conn: &Connection, root: Window, names: Vec<Atom>) {
+        for name in names {
+            let cookie = conn.send_request_checked(&DeleteMonitor { window: root, name });
+            if let Err(err) = conn.check_request(cookie) {
+                eprintln!("deleting monitor {name:?} failed: {err}");
+            }
+        }
+    }
+
+    fn measure(mut f: impl FnMut() -> usize) -> (Duration, usize) {
+        let mut count = f();
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            count = f();
+        }
+
+        (start.elapsed() / ITERATIONS, count)
+    }
+
+    pub fn main() {
+        if env::var_os("DISPLAY").is_none() {
+            eprintln!("DISPLAY is not set, start an X server with the dummy driver first");
+            return;
+        }
+
+        let monitors = env::var("BENCH_MONITORS")
+            .ok()
+            .and_then(|monitors| monitors.parse().ok())
+            .unwrap_or(4usize);
+
+        let (conn, screen_index) =
+            Connection::connect_with_extensions(None, &[Extension::RandR], &[]).unwrap();
+        let screen = conn.get_setup().roots().nth(screen_index as usize).unwrap();
+        let root = screen.root();
+
+        let outputs = get_outputs(&conn, root);
+
+        if env::var_os("BENCH_ALLOW_ANY_DISPLAY").is_none()
+            && !outputs.iter().all(|(_, name)| is_virtual_output(name))
+        {
+            eprintln!(
+                "DISPLAY has outputs other than those of the dummy driver or Xvfb, refusing to \
+                 change its monitors. Set BENCH_ALLOW_ANY_DISPLAY to run anyway."
+            );
+            return;
+        }
+        if outputs.len() < monitors {
+            eprintln!(
+                "the X server has {} outputs, benchmarking {} monitors instead of {monitors}",
+                outputs.len(),
+                outputs.len()
+            );
+        }
+
+        let outputs = outputs
+            .iter()
+            .take(monitors)
+            .map(|&(output, _)| output)
+            .collect::<Vec<_>>();
+        if outputs.is_empty() {
+            eprintln!("the X server has no outputs");
+            return;
+        }
+
+        let names = add_monitors(
+            &conn,
+            root,
+            screen.width_in_pixels(),
+            screen.height_in_pixels(),
+            &outputs,
+        );
+        // also when measuring panics
+        let _names = guard(names, |names| delete_monitors(&conn, root, names));
+
+        let (sequential, count) = measure(|| sequential::get_all(&conn, screen));
+        println!("sequential: {sequential:?} per enumeration of {count} monitors");
+
+        let (pipelined, count) = measure(|| {
+            DisplayInfo::all_with_xcb(&conn, screen_index)
+                .unwrap()
+                .len()
+        });
+        println!("pipelined:  {pipelined:?} per enumeration of {count} monitors");
+
+        println!(
+            "speedup:    {:.1}x",
+            sequential.as_secs_f64() / pipelined.as_secs_f64()
+        );
+    }
+}
+
+fn main() {
+    #[cfg(all(target_family = "unix", not(target_os = "macos")))]
+    x11::main();
+
+    #[cfg(not(all(target_family = "unix", not(target_os = "macos"))))]
+    eprintln!("The X11 enumeration benchmark only runs on Linux and BSD");
+}
@@ -0,0 +1,242 @@
+use std::{
+    cell::RefCell,
+    marker::PhantomData,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use crate::{
+    DisplayInfo, DisplayMode, FromPointFallback, Reflection,
+    error::{DIError, DIResult},
+    find_from_point, find_from_rect,
+    geometry::{Point, Rect},
+    watcher::DisplayEventSource,
+};
+
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+pub use crate::linux::{DisplayContext, WaylandBackend, XorgBackend};
+
+#[cfg(target_os = "macos")]
+pub use crate::macos::MacosBackend;
+
+#[cfg(target_os = "windows")]
+pub use crate::windows::WindowsBackend;
+
+/// A source of display information.
+///
+/// The platform backends implement this trait, and custom implementations can be
+/// registered with [`set_backend`] to answer [`DisplayInfo::all`], [`DisplayInfo::from_point`]
+/// and [`DisplayInfo::from_name`] instead.
+pub trait DisplayBackend: Send + Sync {
+    /// Short backend name, e.g. `"xorg"` or `"wayland"`.
+    fn name(&self) -> &str;
+
+    /// Get all displays.
+    fn get_all(&self) -> DIResult<Vec<DisplayInfo>>;
+
+    /// Get the display whose [`bounds`](DisplayInfo::bounds) contain the point.
+    fn get_from_point(&self, x: i32, y: i32) -> DIResult<DisplayInfo> {
+        self.get_from_point_with(x, y, FromPointFallback::None)
+    }
+
+    /// Get the display containing the point, falling back as chosen by `fallback`.
+    fn get_from_point_with(
+        &self,
+        x: i32,
+        y: i32,
+        fallback: FromPointFallback,
+    ) -> DIResult<DisplayInfo> {
+        let mut display_infos = self.get_all()?;
+
+        let index = find_from_point(&display_infos, Point::new(x, y), fallback)
+            .ok_or_else(|| DIError::new("Get display info failed"))?;
+
+        Ok(display_infos.swap_remove(index))
+    }
+
+    /// Get the display `rect` is on, see [`DisplayInfo::from_rect`].
+    fn get_from_rect(&self, rect: Rect) -> DIResult<DisplayInfo> {
+        let mut display_infos = self.get_all()?;
+
+        let index = find_from_rect(&display_infos, &rect)
+            .ok_or_else(|| DIError::new("Get display info failed"))?;
+
+        Ok(display_infos.swap_remove(index))
+    }
+
+    /// Get the display with the given name.
+    fn get_from_name(&self, name: &str) -> DIResult<DisplayInfo> {
+        self.get_all()?
+            .into_iter()
+            .find(|d| d.name == name)
+            .ok_or_else(|| DIError::new("Get display info failed"))
+    }
+
+    /// Get the primary display.
+    fn get_primary(&self) -> DIResult<DisplayInfo> {
+        self.get_all()?
+            .into_iter()
+            .find(|d| d.is_primary)
+            .ok_or_else(|| DIError::new("Get display info failed"))
+    }
+
+    /// Switch `display` to `mode`, one of its [`DisplayInfo::modes`].
+    fn set_mode(&self, display: &DisplayInfo, mode: &DisplayMode) -> DIResult<()> {
+        let _ = (display, mode);
+        Err(DIError::Unsupported("set_mode"))
+    }
+
+    /// Rotate `display` clock-wise by `rotation` degrees (0, 90, 180 or 270) and mirror it.
+    fn set_rotation(
+        &self,
+        display: &DisplayInfo,
+        rotation: f32,
+        reflection: Reflection,
+    ) -> DIResult<()> {
+        let _ = (display, rotation, reflection);
+        Err(DIError::Unsupported("set_rotation"))
+    }
+
+    /// Make `display` the primary display.
+    fn set_primary(&self, display: &DisplayInfo) -> DIResult<()> {
+        let _ = display;
+        Err(DIError::Unsupported("set_primary"))
+    }
+
+    /// Subscribe to display configuration changes, used by
+    /// [`DisplayWatcher`](crate::watcher::DisplayWatcher).
+    fn watch(&self) -> DIResult<Box<dyn DisplayEventSource>> {
+        Err(DIError::Unsupported("watch"))
+    }
+}
+
+static BACKEND: RwLock<Option<Arc<dyn DisplayBackend>>> = RwLock::new(None);
+
+thread_local! {
+    static THREAD_BACKEND: RefCell<Option<Arc<dyn DisplayBackend>>> = const { RefCell::new(None) };
+}
+
+/// Use `backend` for all following display queries.
+pub fn set_backend<B: DisplayBackend + 'static>(backend: B) {
+    *BACKEND.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(backend));
+}
+
+/// Go back to the platform backend selected at runtime.
+pub fn reset_backend() {
+    *BACKEND.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Use `backend` for the display queries of the current thread until the returned guard is
+/// dropped, in place of the backend of [`set_backend`]. Tests running in parallel can each
+/// register their own backend this way.
+///
+/// ```
+/// use display_info::{DisplayInfo, backend::{self, DisplayBackend}, error::DIResult};
+///
+/// struct NoDisplays;
+///
+/// impl DisplayBackend for NoDisplays {
+///     fn name(&self) -> &str {
+///         "none"
+///     }
+///
+///     fn get_all(&self) -> DIResult<Vec<DisplayInfo>> {
+///         Ok(Vec::new())
+///     }
+/// }
+///
+/// let guard = backend::set_thread_backend(NoDisplays);
+/// assert!(DisplayInfo::all().unwrap().is_empty());
+/// drop(guard);
+/// ```
+pub fn set_thread_backend<B: DisplayBackend + 'static>(backend: B) -> ThreadBackendGuard {
+    let previous = THREAD_BACKEND.with_borrow_mut(|thread_backend| {
+        thread_backend.replace(Arc::new(backend) as Arc<dyn DisplayBackend>)
+    });
+
+    ThreadBackendGuard {
+        previous,
+        _not_send: PhantomData,
+    }
+}
+
+/// Restores the previous backend of the thread when dropped, see [`set_thread_backend`].
+#[must_use = "the backend is only used until the guard is dropped"]
+pub struct ThreadBackendGuard {
+    previous: Option<Arc<dyn DisplayBackend>>,
+    /// The guard belongs to the thread whose backend it restores.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ThreadBackendGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        THREAD_BACKEND.with_borrow_mut(|thread_backend| *thread_backend = previous);
+    }
+}
+
+/// The backend of the current thread, the registered backend, or the platform backend when none
+/// is registered.
+pub fn current_backend() -> Arc<dyn DisplayBackend> {
+    THREAD_BACKEND
+        .with_borrow(Option::clone)
+        .or_else(|| {
+            BACKEND
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone()
+        })
+        .unwrap_or_else(default_backend)
+}
+
+#[cfg(feature = "mock")]
+fn default_backend() -> Arc<dyn DisplayBackend> {
+    use std::{ffi::OsString, sync::Mutex};
+
+    /// The fixture path and its backend, `None` when it failed to load.
+    type FixtureCache = Option<(OsString, Option<Arc<dyn DisplayBackend>>)>;
+
+    // loaded once per path, not on every query
+    static FIXTURE: Mutex<FixtureCache> = Mutex::new(None);
+
+    if let Some(path) = std::env::var_os(crate::mock::MOCK_FIXTURE_ENV) {
+        let mut fixture = FIXTURE.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if fixture.as_ref().is_none_or(|(loaded, _)| *loaded != path) {
+            let backend = crate::mock::MockBackend::from_file(&path)
+                .map(|backend| Arc::new(backend) as Arc<dyn DisplayBackend>)
+                .map_err(|err| log::error!("Load mock fixture {:?} failed: {}", path, err))
+                .ok();
+            *fixture = Some((path, backend));
+        }
+
+        if let Some((_, Some(backend))) = fixture.as_ref() {
+            return backend.clone();
+        }
+    }
+
+    platform_backend()
+}
+
+#[cfg(not(feature = "mock"))]
+fn default_backend() -> Arc<dyn DisplayBackend> {
+    platform_backend()
+}
+
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+fn platform_backend() -> Arc<dyn DisplayBackend> {
+    if crate::linux::is_wayland() {
+        Arc::new(WaylandBackend::new())
+    } else {
+        Arc::new(XorgBackend)
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn platform_backend() -> Arc<dyn DisplayBackend> {
+    Arc::new(MacosBackend)
+}
+
+#[cfg(target_os = "windows")]
+fn platform_backend() -> Arc<dyn DisplayBackend> {
+    Arc::new(WindowsBackend)
+}
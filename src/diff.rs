@@ -0,0 +1,401 @@
+//! Changes between two display lists, e.g. before and after resuming from suspend.
+//!
+//! ```no_run
+//! use display_info::{DisplayInfo, diff::DisplayDiff};
+//!
+//! let before = DisplayInfo::all().unwrap();
+//! // ...
+//! let after = DisplayInfo::all().unwrap();
+//!
+//! let diff = DisplayDiff::new(&before, &after);
+//! for change in diff.changed {
+//!     println!("{} changed: {:?}", change.new.name, change.changes);
+//! }
+//! ```
+
+use crate::{
+    DisplayInfo, DisplayMode,
+    geometry::{Rect, Size},
+};
+
+/// A changed property of a display, with its old and new value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldChange {
+    Name {
+        old: String,
+        new: String,
+    },
+    FriendlyName {
+        old: String,
+        new: String,
+    },
+    /// `(x, y)`
+    Position {
+        old: (i32, i32),
+        new: (i32, i32),
+    },
+    /// `(width, height)`
+    Size {
+        old: (u32, u32),
+        new: (u32, u32),
+    },
+    /// `(width_mm, height_mm)`
+    PhysicalSize {
+        old: (i32, i32),
+        new: (i32, i32),
+    },
+    Rotation {
+        old: f32,
+        new: f32,
+    },
+    ScaleFactor {
+        old: f32,
+        new: f32,
+    },
+    Frequency {
+        old: f32,
+        new: f32,
+    },
+    Primary {
+        old: bool,
+        new: bool,
+    },
+    /// The mode the display uses.
+    CurrentMode {
+        old: Option<DisplayMode>,
+        new: Option<DisplayMode>,
+    },
+    WorkArea {
+        old: Rect,
+        new: Rect,
+    },
+    WorkAreaSize {
+        old: Option<Size>,
+        new: Option<Size>,
+    },
+    /// The modes the display supports.
+    Modes {
+        old: Vec<DisplayMode>,
+        new: Vec<DisplayMode>,
+    },
+}
+
+/// A display present in both lists whose properties changed.
+#[derive(Debug, Clone)]
+pub struct DisplayChange {
+    pub old: DisplayInfo,
+    pub new: DisplayInfo,
+    /// Never empty.
+    pub changes: Vec<FieldChange>,
+}
+
+impl DisplayChange {
+    /// Compare two states of the same display, `None` when nothing changed.
+    pub fn new(old: &DisplayInfo, new: &DisplayInfo) -> Option<DisplayChange> {
+        let mut changes = Vec::new();
+
+        if old.name != new.name {
+            changes.push(FieldChange::Name {
+                old: old.name.clone(),
+                new: new.name.clone(),
+            });
+        }
+        if old.friendly_name != new.friendly_name {
+            changes.push(FieldChange::FriendlyName {
+                old: old.friendly_name.clone(),
+                new: new.friendly_name.clone(),
+            });
+        }
+        if (old.x, old.y) != (new.x, new.y) {
+            changes.push(FieldChange::Position {
+                old: (old.x, old.y),
+                new: (new.x, new.y),
+            });
+        }
+        if (old.width, old.height) != (new.width, new.height) {
+            changes.push(FieldChange::Size {
+                old: (old.width, old.height),
+                new: (new.width, new.height),
+            });
+        }
+        if (old.width_mm, old.height_mm) != (new.width_mm, new.height_mm) {
+            changes.push(FieldChange::PhysicalSize {
+                old: (old.width_mm, old.height_mm),
+                new: (new.width_mm, new.height_mm),
+            });
+        }
+        if old.rotation != new.rotation {
+            changes.push(FieldChange::Rotation {
+                old: old.rotation,
+                new: new.rotation,
+            });
+        }
+        if old.scale_factor != new.scale_factor {
+            changes.push(FieldChange::ScaleFactor {
+                old: old.scale_factor,
+                new: new.scale_factor,
+            });
+        }
+        if old.frequency != new.frequency {
+            changes.push(FieldChange::Frequency {
+                old: old.frequency,
+                new: new.frequency,
+            });
+        }
+        if old.is_primary != new.is_primary {
+            changes.push(FieldChange::Primary {
+                old: old.is_primary,
+                new: new.is_primary,
+            });
+        }
+
+        if old.work_area != new.work_area {
+            changes.push(FieldChange::WorkArea {
+                old: old.work_area,
+                new: new.work_area,
+            });
+        }
+        if old.work_area_size != new.work_area_size {
+            changes.push(FieldChange::WorkAreaSize {
+                old: old.work_area_size,
+                new: new.work_area_size,
+            });
+        }
+
+        let current_mode = |d: &DisplayInfo| d.modes.iter().find(|m| m.is_current).copied();
+        if current_mode(old) != current_mode(new) {
+            changes.push(FieldChange::CurrentMode {
+                old: current_mode(old),
+                new: current_mode(new),
+            });
+        }
+
+        // the current flag moving between modes is not a change of the supported modes
+        let supported_modes = |d: &DisplayInfo| {
+            d.modes
+                .iter()
+                .map(|m| DisplayMode {
+                    is_current: false,
+                    ..*m
+                })
+                .collect::<Vec<_>>()
+        };
+        if supported_modes(old) != supported_modes(new) {
+            changes.push(FieldChange::Modes {
+                old: old.modes.clone(),
+                new: new.modes.clone(),
+            });
+        }
+
+        (!changes.is_empty()).then(|| DisplayChange {
+            old: old.clone(),
+            new: new.clone(),
+            changes,
+        })
+    }
+}
+
+/// The changes from one display list to another.
+#[derive(Debug, Clone, Default)]
+pub struct DisplayDiff {
+    /// Displays only in the new list.
+    pub added: Vec<DisplayInfo>,
+    /// Displays only in the old list.
+    pub removed: Vec<DisplayInfo>,
+    pub changed: Vec<DisplayChange>,
+}
+
+impl DisplayDiff {
+    /// Match the displays of `old` and `new` with [`is_same_display`] and compare them.
+    /// Identical monitors sharing a stable id are told apart by [`DisplayInfo::id`].
+    pub fn new(old: &[DisplayInfo], new: &[DisplayInfo]) -> DisplayDiff {
+        let mut diff = DisplayDiff::default();
+        let mut matched = vec![false; old.len()];
+
+        for new_display in new {
+            let candidates = || {
+                (0..old.len())
+                    .filter(|&index| !matched[index] && is_same_display(&old[index], new_display))
+            };
+            let old_index = candidates()
+                .find(|&index| old[index].id == new_display.id)
+                .or_else(|| candidates().next());
+
+            match old_index {
+                Some(index) => {
+                    matched[index] = true;
+                    diff.changed
+                        .extend(DisplayChange::new(&old[index], new_display));
+                }
+                None => diff.added.push(new_display.clone()),
+            }
+        }
+
+        diff.removed = old
+            .iter()
+            .zip(matched)
+            .filter(|(_, matched)| !matched)
+            .map(|(d, _)| d.clone())
+            .collect();
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Whether `a` and `b` are the same display, by [`DisplayInfo::stable_id`] or, when either has
+/// none, by [`DisplayInfo::id`].
+pub fn is_same_display(a: &DisplayInfo, b: &DisplayInfo) -> bool {
+    if !a.stable_id.is_empty() && !b.stable_id.is_empty() {
+        a.stable_id == b.stable_id
+    } else {
+        a.id == b.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::display;
+
+    use super::*;
+
+    fn mode(id: u32, width: u32, height: u32, is_current: bool) -> DisplayMode {
+        DisplayMode {
+            id,
+            width,
+            height,
+            refresh_rate: 60.0,
+            is_current,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn match_by_stable_id() {
+        let old = [display(1, 0, 0, 1920, 1080)];
+        // the same monitor after a reconnect, with a new id
+        let new = DisplayInfo {
+            id: 7,
+            ..old[0].clone()
+        };
+
+        let diff = DisplayDiff::new(&old, &[new]);
+        assert!(diff.is_empty(), "{diff:?}");
+    }
+
+    #[test]
+    fn match_by_id_without_stable_id() {
+        let mut old = display(1, 0, 0, 1920, 1080);
+        old.stable_id.clear();
+        let mut new = display(1, 1920, 0, 1920, 1080);
+        new.stable_id = "GSM-5B09-903NTPC1A234".to_string();
+
+        let diff = DisplayDiff::new(&[old], &[new]);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(
+            diff.changed[0].changes,
+            [
+                FieldChange::Position {
+                    old: (0, 0),
+                    new: (1920, 0)
+                },
+                FieldChange::WorkArea {
+                    old: Rect::new(0, 0, 1920, 1080),
+                    new: Rect::new(1920, 0, 1920, 1080)
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn different_stable_ids_are_different_displays() {
+        let old = display(1, 0, 0, 1920, 1080);
+        let mut new = display(1, 0, 0, 1920, 1080);
+        new.stable_id = "DEL-D0A1-PXM4R61PC0ML".to_string();
+
+        let diff = DisplayDiff::new(&[old], &[new]);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.removed.len(), 1);
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn identical_monitors() {
+        let twin = |id, x| {
+            let mut display = display(id, x, 0, 1920, 1080);
+            display.stable_id = "GSM-5B09-DP".to_string();
+            display
+        };
+
+        let old = [twin(1, 0), twin(2, 1920)];
+        let diff = DisplayDiff::new(&old, &[twin(2, 1920), twin(1, 0)]);
+        assert!(diff.is_empty(), "{diff:?}");
+
+        let diff = DisplayDiff::new(&old, &[twin(2, 1920)]);
+        assert!(diff.added.is_empty() && diff.changed.is_empty());
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].id, 1);
+    }
+
+    #[test]
+    fn added_and_removed() {
+        let old = [
+            display(1, 0, 0, 1920, 1080),
+            display(2, 1920, 0, 1920, 1080),
+        ];
+        let new = [
+            display(1, 0, 0, 1920, 1080),
+            display(3, -1920, 0, 1920, 1080),
+        ];
+
+        let diff = DisplayDiff::new(&old, &new);
+        assert_eq!(diff.added.iter().map(|d| d.id).collect::<Vec<_>>(), [3]);
+        assert_eq!(diff.removed.iter().map(|d| d.id).collect::<Vec<_>>(), [2]);
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn current_mode_moving_is_not_a_modes_change() {
+        let mut old = display(1, 0, 0, 1920, 1080);
+        old.modes = vec![mode(10, 1920, 1080, true), mode(11, 1280, 720, false)];
+        let mut new = display(1, 0, 0, 1280, 720);
+        new.work_area = old.work_area;
+        new.modes = vec![mode(10, 1920, 1080, false), mode(11, 1280, 720, true)];
+
+        let change = DisplayChange::new(&old, &new).unwrap();
+        assert_eq!(
+            change.changes,
+            [
+                FieldChange::Size {
+                    old: (1920, 1080),
+                    new: (1280, 720)
+                },
+                FieldChange::CurrentMode {
+                    old: Some(old.modes[0]),
+                    new: Some(new.modes[1])
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn supported_modes_change() {
+        let mut old = display(1, 0, 0, 1920, 1080);
+        old.modes = vec![mode(10, 1920, 1080, true)];
+        let mut new = old.clone();
+        new.modes.push(mode(11, 1280, 720, false));
+
+        let change = DisplayChange::new(&old, &new).unwrap();
+        assert_eq!(
+            change.changes,
+            [FieldChange::Modes {
+                old: old.modes.clone(),
+                new: new.modes.clone()
+            }]
+        );
+        assert!(DisplayChange::new(&old, &old).is_none());
+    }
+}
@@ -0,0 +1,397 @@
+//! CTA-861 extension blocks.
+
+use super::DetailedTiming;
+
+pub(crate) const CTA_EXTENSION_TAG: u8 = 0x02;
+
+const AUDIO_DATA_BLOCK: u8 = 1;
+const VIDEO_DATA_BLOCK: u8 = 2;
+const VENDOR_SPECIFIC_DATA_BLOCK: u8 = 3;
+const EXTENDED_DATA_BLOCK: u8 = 7;
+
+const COLORIMETRY_DATA_BLOCK: u8 = 5;
+const HDR_STATIC_METADATA_DATA_BLOCK: u8 = 6;
+
+const HDMI_OUI: u32 = 0x000C03;
+const HDMI_FORUM_OUI: u32 = 0xC45DD8;
+
+/// A short video descriptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShortVideoDescriptor {
+    /// CTA-861 Video Identification Code.
+    pub vic: u8,
+    pub native: bool,
+}
+
+/// A short audio descriptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShortAudioDescriptor {
+    /// Audio format code, 1 is LPCM, 2 is AC-3, 7 is DTS, 10 is E-AC-3, ...
+    pub format_code: u8,
+    pub max_channels: u8,
+    /// Bit mask of 32, 44.1, 48, 88.2, 96, 176.4 and 192 kHz, lowest bit first.
+    pub sample_rates: u8,
+    /// Bit depths for LPCM, maximum bit rate or format specific data otherwise.
+    pub extra: u8,
+}
+
+impl ShortAudioDescriptor {
+    /// Supported sample rates in Hz.
+    pub fn sample_rates_hz(&self) -> Vec<u32> {
+        [32000, 44100, 48000, 88200, 96000, 176400, 192000]
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| self.sample_rates & (1 << i) != 0)
+            .map(|(_, rate)| rate)
+            .collect()
+    }
+}
+
+/// The HDMI Licensing vendor-specific data block (HDMI 1.4).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HdmiVsdb {
+    /// CEC physical address, e.g. `[1, 0, 0, 0]` for 1.0.0.0.
+    pub physical_address: [u8; 4],
+    pub supports_ai: bool,
+    pub deep_color_30: bool,
+    pub deep_color_36: bool,
+    pub deep_color_48: bool,
+    pub deep_color_y444: bool,
+    pub dvi_dual: bool,
+    pub max_tmds_clock_mhz: Option<u16>,
+}
+
+/// The HDMI Forum vendor-specific data block (HDMI 2.x).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HdmiForumVsdb {
+    pub version: u8,
+    pub max_tmds_character_rate_mhz: u16,
+    pub scdc_present: bool,
+    /// Encoded maximum FRL rate, 0 when FRL is not supported.
+    pub max_frl_rate: u8,
+}
+
+/// Colorimetry data block flags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Colorimetry {
+    pub xvycc601: bool,
+    pub xvycc709: bool,
+    pub sycc601: bool,
+    pub opycc601: bool,
+    pub oprgb: bool,
+    pub bt2020_cycc: bool,
+    pub bt2020_ycc: bool,
+    pub bt2020_rgb: bool,
+    pub dci_p3: bool,
+}
+
+/// HDR static metadata data block.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HdrStaticMetadata {
+    /// Traditional gamma, SDR luminance range.
+    pub sdr: bool,
+    /// Traditional gamma, HDR luminance range.
+    pub traditional_hdr: bool,
+    /// SMPTE ST 2084 (PQ).
+    pub pq: bool,
+    /// Hybrid Log-Gamma.
+    pub hlg: bool,
+    pub static_metadata_type1: bool,
+    /// Desired content max luminance in cd/m².
+    pub max_luminance: Option<f32>,
+    /// Desired content max frame-average luminance in cd/m².
+    pub max_frame_avg_luminance: Option<f32>,
+    /// Desired content min luminance in cd/m².
+    pub min_luminance: Option<f32>,
+}
+
+/// A CTA-861 extension block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CtaExtension {
+    pub revision: u8,
+    pub underscan: bool,
+    pub basic_audio: bool,
+    pub ycbcr444: bool,
+    pub ycbcr422: bool,
+    pub native_dtd_count: u8,
+    pub video: Vec<ShortVideoDescriptor>,
+    pub audio: Vec<ShortAudioDescriptor>,
+    pub hdmi: Option<HdmiVsdb>,
+    pub hdmi_forum: Option<HdmiForumVsdb>,
+    pub colorimetry: Option<Colorimetry>,
+    pub hdr_static_metadata: Option<HdrStaticMetadata>,
+    pub detailed_timings: Vec<DetailedTiming>,
+}
+
+impl CtaExtension {
+    pub(crate) fn parse(block: &[u8]) -> CtaExtension {
+        // offset of the first detailed timing descriptor, data blocks sit between 4 and it
+        let dtd_offset = (block[2] as usize).clamp(4, 127);
+
+        let mut extension = CtaExtension {
+            revision: block[1],
+            underscan: block[3] & 0x80 != 0,
+            basic_audio: block[3] & 0x40 != 0,
+            ycbcr444: block[3] & 0x20 != 0,
+            ycbcr422: block[3] & 0x10 != 0,
+            native_dtd_count: block[3] & 0x0F,
+            video: Vec::new(),
+            audio: Vec::new(),
+            hdmi: None,
+            hdmi_forum: None,
+            colorimetry: None,
+            hdr_static_metadata: None,
+            detailed_timings: Vec::new(),
+        };
+
+        if block[2] != 0 {
+            let mut offset = 4;
+            while offset < dtd_offset {
+                let tag = block[offset] >> 5;
+                let len = (block[offset] & 0x1F) as usize;
+                let Some(payload) = block.get(offset + 1..offset + 1 + len) else {
+                    break;
+                };
+                extension.parse_data_block(tag, payload);
+                offset += 1 + len;
+            }
+        }
+
+        let mut offset = dtd_offset;
+        while offset + 18 <= 127 {
+            match DetailedTiming::parse(&block[offset..offset + 18]) {
+                Some(detailed_timing) => extension.detailed_timings.push(detailed_timing),
+                None => break,
+            }
+            offset += 18;
+        }
+
+        extension
+    }
+
+    fn parse_data_block(&mut self, tag: u8, payload: &[u8]) {
+        match tag {
+            AUDIO_DATA_BLOCK => {
+                self.audio
+                    .extend(payload.chunks_exact(3).map(|sad| ShortAudioDescriptor {
+                        format_code: (sad[0] >> 3) & 0x0F,
+                        max_channels: (sad[0] & 0x07) + 1,
+                        sample_rates: sad[1] & 0x7F,
+                        extra: sad[2],
+                    }));
+            }
+            VIDEO_DATA_BLOCK => {
+                self.video.extend(payload.iter().map(|&svd| {
+                    if (129..=192).contains(&svd) {
+                        ShortVideoDescriptor {
+                            vic: svd & 0x7F,
+                            native: true,
+                        }
+                    } else {
+                        ShortVideoDescriptor {
+                            vic: svd,
+                            native: false,
+                        }
+                    }
+                }));
+            }
+            VENDOR_SPECIFIC_DATA_BLOCK if payload.len() >= 3 => {
+                let oui = u32::from_le_bytes([payload[0], payload[1], payload[2], 0]);
+                match oui {
+                    HDMI_OUI => self.hdmi = parse_hdmi_vsdb(payload),
+                    HDMI_FORUM_OUI => self.hdmi_forum = parse_hdmi_forum_vsdb(payload),
+                    _ => {}
+                }
+            }
+            EXTENDED_DATA_BLOCK if !payload.is_empty() => match payload[0] {
+                COLORIMETRY_DATA_BLOCK => self.colorimetry = parse_colorimetry(payload),
+                HDR_STATIC_METADATA_DATA_BLOCK => {
+                    self.hdr_static_metadata = parse_hdr_static_metadata(payload)
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+fn parse_hdmi_vsdb(payload: &[u8]) -> Option<HdmiVsdb> {
+    if payload.len() < 5 {
+        return None;
+    }
+
+    let flags = payload.get(5).copied().unwrap_or_default();
+
+    Some(HdmiVsdb {
+        physical_address: [
+            payload[3] >> 4,
+            payload[3] & 0x0F,
+            payload[4] >> 4,
+            payload[4] & 0x0F,
+        ],
+        supports_ai: flags & 0x80 != 0,
+        deep_color_48: flags & 0x40 != 0,
+        deep_color_36: flags & 0x20 != 0,
+        deep_color_30: flags & 0x10 != 0,
+        deep_color_y444: flags & 0x08 != 0,
+        dvi_dual: flags & 0x01 != 0,
+        max_tmds_clock_mhz: payload
+            .get(6)
+            .filter(|&&clock| clock != 0)
+            .map(|&clock| clock as u16 * 5),
+    })
+}
+
+fn parse_hdmi_forum_vsdb(payload: &[u8]) -> Option<HdmiForumVsdb> {
+    if payload.len() < 6 {
+        return None;
+    }
+
+    Some(HdmiForumVsdb {
+        version: payload[3],
+        max_tmds_character_rate_mhz: payload[4] as u16 * 5,
+        scdc_present: payload[5] & 0x80 != 0,
+        max_frl_rate: payload.get(6).map(|b| b >> 4).unwrap_or_default(),
+    })
+}
+
+fn parse_colorimetry(payload: &[u8]) -> Option<Colorimetry> {
+    let flags = *payload.get(1)?;
+    let flags2 = payload.get(2).copied().unwrap_or_default();
+
+    Some(Colorimetry {
+        xvycc601: flags & 0x01 != 0,
+        xvycc709: flags & 0x02 != 0,
+        sycc601: flags & 0x04 != 0,
+        opycc601: flags & 0x08 != 0,
+        oprgb: flags & 0x10 != 0,
+        bt2020_cycc: flags & 0x20 != 0,
+        bt2020_ycc: flags & 0x40 != 0,
+        bt2020_rgb: flags & 0x80 != 0,
+        dci_p3: flags2 & 0x80 != 0,
+    })
+}
+
+fn parse_hdr_static_metadata(payload: &[u8]) -> Option<HdrStaticMetadata> {
+    let eotfs = *payload.get(1)?;
+
+    // per CTA-861-G 7.5.13, luminance = 50 * 2^(cv / 32)
+    let luminance = |cv: &u8| 50.0 * 2f32.powf(*cv as f32 / 32.0);
+    let max_luminance = payload.get(3).filter(|&&cv| cv != 0).map(luminance);
+    let max_frame_avg_luminance = payload.get(4).filter(|&&cv| cv != 0).map(luminance);
+    let min_luminance = payload.get(5).zip(max_luminance).map(|(&cv, max)| {
+        let ratio = cv as f32 / 255.0;
+        max * ratio * ratio / 100.0
+    });
+
+    Some(HdrStaticMetadata {
+        sdr: eotfs & 0x01 != 0,
+        traditional_hdr: eotfs & 0x02 != 0,
+        pq: eotfs & 0x04 != 0,
+        hlg: eotfs & 0x08 != 0,
+        static_metadata_type1: payload.get(2).is_some_and(|b| b & 0x01 != 0),
+        max_luminance,
+        max_frame_avg_luminance,
+        min_luminance,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HdrInfo, edid::Edid};
+
+    use super::*;
+
+    const HDR: &[u8] = include_bytes!("../../tests/fixtures/edid/hdr.bin");
+
+    fn parse() -> CtaExtension {
+        Edid::parse(HDR).unwrap().cta().cloned().unwrap()
+    }
+
+    fn assert_close(actual: Option<f32>, expected: f32) {
+        let actual = actual.unwrap();
+        assert!(
+            (actual - expected).abs() < 0.001,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn parse_hdr_static_metadata() {
+        let metadata = parse().hdr_static_metadata.unwrap();
+
+        assert!(metadata.sdr);
+        assert!(!metadata.traditional_hdr);
+        assert!(metadata.pq);
+        assert!(!metadata.hlg);
+        assert!(metadata.static_metadata_type1);
+        assert_close(metadata.max_luminance, 400.0);
+        assert_close(metadata.max_frame_avg_luminance, 200.0);
+        assert_close(metadata.min_luminance, 0.16);
+    }
+
+    #[test]
+    fn hdr_info_from_edid() {
+        let hdr = HdrInfo::from_edid(&Edid::parse(HDR).unwrap()).unwrap();
+
+        assert!(hdr.sdr && hdr.pq && !hdr.hlg);
+        assert!(hdr.bt2020);
+        assert_close(hdr.max_luminance, 400.0);
+        assert_close(hdr.max_frame_avg_luminance, 200.0);
+        assert_close(hdr.min_luminance, 0.16);
+    }
+
+    #[test]
+    fn parse_data_blocks() {
+        let cta = parse();
+
+        assert_eq!(cta.revision, 3);
+        assert!(cta.underscan && cta.basic_audio && cta.ycbcr444 && cta.ycbcr422);
+        assert_eq!(cta.native_dtd_count, 1);
+
+        let vics: Vec<(u8, bool)> = cta.video.iter().map(|svd| (svd.vic, svd.native)).collect();
+        assert_eq!(
+            vics,
+            [
+                (16, true),
+                (4, false),
+                (3, false),
+                (97, false),
+                (96, false),
+                (101, false),
+                (102, false)
+            ]
+        );
+
+        let [audio] = cta.audio[..] else {
+            panic!("expected one audio descriptor, got {:?}", cta.audio);
+        };
+        assert_eq!((audio.format_code, audio.max_channels), (1, 2));
+        assert_eq!(audio.sample_rates_hz(), [32000, 44100, 48000]);
+
+        let hdmi = cta.hdmi.unwrap();
+        assert_eq!(hdmi.physical_address, [1, 0, 0, 0]);
+        assert!(hdmi.deep_color_30 && hdmi.deep_color_36 && !hdmi.deep_color_48);
+        assert!(hdmi.deep_color_y444);
+        assert_eq!(hdmi.max_tmds_clock_mhz, Some(300));
+
+        let hdmi_forum = cta.hdmi_forum.unwrap();
+        assert_eq!(hdmi_forum.version, 1);
+        assert_eq!(hdmi_forum.max_tmds_character_rate_mhz, 600);
+        assert!(hdmi_forum.scdc_present);
+        assert_eq!(hdmi_forum.max_frl_rate, 0);
+
+        let colorimetry = cta.colorimetry.unwrap();
+        assert!(colorimetry.bt2020_rgb && colorimetry.bt2020_ycc && !colorimetry.bt2020_cycc);
+        assert!(!colorimetry.dci_p3);
+
+        let [timing] = cta.detailed_timings[..] else {
+            panic!(
+                "expected one detailed timing, got {:?}",
+                cta.detailed_timings
+            );
+        };
+        assert_eq!((timing.h_active, timing.v_active), (3840, 2160));
+        assert!((timing.refresh_rate() - 30.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,208 @@
+//! DisplayID 1.3 and 2.0 extension blocks.
+
+pub(crate) const DISPLAY_ID_EXTENSION_TAG: u8 = 0x70;
+
+const TYPE_I_TIMING_BLOCK: u8 = 0x03;
+const TILED_TOPOLOGY_BLOCK: u8 = 0x12;
+const TYPE_VII_TIMING_BLOCK: u8 = 0x22;
+const TILED_TOPOLOGY_BLOCK_V2: u8 = 0x28;
+
+/// A Type I (DisplayID 1.x) or Type VII (DisplayID 2.0) detailed timing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayIdTiming {
+    /// Pixel clock in kHz.
+    pub pixel_clock_khz: u32,
+    pub h_active: u16,
+    pub h_blank: u16,
+    pub h_sync_offset: u16,
+    pub h_sync_width: u16,
+    pub v_active: u16,
+    pub v_blank: u16,
+    pub v_sync_offset: u16,
+    pub v_sync_width: u16,
+    pub interlaced: bool,
+    pub preferred: bool,
+}
+
+impl DisplayIdTiming {
+    fn parse(bytes: &[u8], khz_per_unit: u32) -> DisplayIdTiming {
+        let clock = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]) + 1;
+        // every field is stored minus one, sync offsets carry the polarity in their top bit
+        let field = |i: usize, mask: u16| {
+            (u16::from_le_bytes([bytes[i], bytes[i + 1]]) & mask).saturating_add(1)
+        };
+
+        DisplayIdTiming {
+            pixel_clock_khz: clock * khz_per_unit,
+            h_active: field(4, 0xFFFF),
+            h_blank: field(6, 0xFFFF),
+            h_sync_offset: field(8, 0x7FFF),
+            h_sync_width: field(10, 0xFFFF),
+            v_active: field(12, 0xFFFF),
+            v_blank: field(14, 0xFFFF),
+            v_sync_offset: field(16, 0x7FFF),
+            v_sync_width: field(18, 0xFFFF),
+            interlaced: bytes[3] & 0x10 != 0,
+            preferred: bytes[3] & 0x80 != 0,
+        }
+    }
+
+    /// The refresh rate in Hz.
+    pub fn refresh_rate(&self) -> f32 {
+        let h_total = self.h_active as f32 + self.h_blank as f32;
+        let v_total = self.v_active as f32 + self.v_blank as f32;
+
+        self.pixel_clock_khz as f32 * 1000.0 / (h_total * v_total)
+    }
+}
+
+/// Position of one tile of a display built from several tiles, e.g. a 5K monitor driven
+/// over two streams.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TiledTopology {
+    /// Whether all tiles are in a single physical enclosure.
+    pub single_enclosure: bool,
+    pub h_tiles: u8,
+    pub v_tiles: u8,
+    pub h_location: u8,
+    pub v_location: u8,
+    pub tile_width: u16,
+    pub tile_height: u16,
+    pub vendor_id: String,
+    pub product_code: u16,
+    pub serial_number: u32,
+}
+
+impl TiledTopology {
+    fn parse(payload: &[u8]) -> Option<TiledTopology> {
+        if payload.len() < 22 {
+            return None;
+        }
+
+        let topology = &payload[1..4];
+
+        Some(TiledTopology {
+            single_enclosure: payload[0] & 0x80 != 0,
+            h_tiles: ((topology[0] >> 4) | ((topology[2] >> 2) & 0x30)) + 1,
+            v_tiles: ((topology[0] & 0x0F) | (topology[2] & 0x30)) + 1,
+            h_location: (topology[1] >> 4) | (((topology[2] >> 2) & 0x03) << 4),
+            v_location: (topology[1] & 0x0F) | ((topology[2] & 0x03) << 4),
+            tile_width: u16::from_le_bytes([payload[4], payload[5]]).saturating_add(1),
+            tile_height: u16::from_le_bytes([payload[6], payload[7]]).saturating_add(1),
+            vendor_id: String::from_utf8_lossy(&payload[13..16]).to_string(),
+            product_code: u16::from_le_bytes([payload[16], payload[17]]),
+            serial_number: u32::from_le_bytes([payload[18], payload[19], payload[20], payload[21]]),
+        })
+    }
+}
+
+/// A DisplayID extension block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayIdExtension {
+    /// DisplayID major version, 1 or 2.
+    pub version: u8,
+    pub revision: u8,
+    /// Product type (1.x) or primary use case (2.0).
+    pub product_type: u8,
+    pub tiled_topology: Option<TiledTopology>,
+    pub timings: Vec<DisplayIdTiming>,
+}
+
+impl DisplayIdExtension {
+    pub(crate) fn parse(block: &[u8]) -> DisplayIdExtension {
+        let section = &block[1..];
+        let section_end = (4 + section[1] as usize).min(section.len());
+
+        let mut extension = DisplayIdExtension {
+            version: section[0] >> 4,
+            revision: section[0] & 0x0F,
+            product_type: section[2],
+            tiled_topology: None,
+            timings: Vec::new(),
+        };
+
+        let mut offset = 4;
+        while offset + 3 <= section_end {
+            let tag = section[offset];
+            let len = section[offset + 2] as usize;
+            let Some(payload) = section.get(offset + 3..offset + 3 + len) else {
+                break;
+            };
+
+            match tag {
+                TYPE_I_TIMING_BLOCK => extension.timings.extend(
+                    payload
+                        .chunks_exact(20)
+                        .map(|timing| DisplayIdTiming::parse(timing, 10)),
+                ),
+                TYPE_VII_TIMING_BLOCK => extension.timings.extend(
+                    payload
+                        .chunks_exact(20)
+                        .map(|timing| DisplayIdTiming::parse(timing, 1)),
+                ),
+                TILED_TOPOLOGY_BLOCK | TILED_TOPOLOGY_BLOCK_V2 => {
+                    extension.tiled_topology = TiledTopology::parse(payload)
+                }
+                // padding, no more data blocks
+                0 if len == 0 => break,
+                _ => {}
+            }
+
+            offset += 3 + len;
+        }
+
+        extension
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edid::Edid;
+
+    use super::*;
+
+    const TILED: &[u8] = include_bytes!("../../tests/fixtures/edid/tiled.bin");
+
+    fn parse() -> DisplayIdExtension {
+        Edid::parse(TILED).unwrap().display_id().cloned().unwrap()
+    }
+
+    #[test]
+    fn parse_tiled_topology() {
+        let display_id = parse();
+
+        assert_eq!((display_id.version, display_id.revision), (1, 2));
+        assert_eq!(display_id.product_type, 3);
+        assert_eq!(
+            display_id.tiled_topology,
+            Some(TiledTopology {
+                single_enclosure: true,
+                h_tiles: 2,
+                v_tiles: 1,
+                h_location: 1,
+                v_location: 0,
+                tile_width: 2560,
+                tile_height: 2880,
+                vendor_id: "DEL".to_string(),
+                product_code: 0xD0A1,
+                serial_number: 0x4C4B4A31,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_type_i_timing() {
+        let [timing] = parse().timings[..] else {
+            panic!("expected one timing");
+        };
+
+        assert_eq!(timing.pixel_clock_khz, 241500);
+        assert_eq!((timing.h_active, timing.h_blank), (2560, 160));
+        assert_eq!((timing.h_sync_offset, timing.h_sync_width), (48, 32));
+        assert_eq!((timing.v_active, timing.v_blank), (2880, 62));
+        assert_eq!((timing.v_sync_offset, timing.v_sync_width), (3, 5));
+        assert!(timing.preferred);
+        assert!(!timing.interlaced);
+        assert!((timing.refresh_rate() - 30.18).abs() < 0.01);
+    }
+}
@@ -0,0 +1,346 @@
+//! Decoding of EDID (Extended Display Identification Data) blobs.
+//!
+//! [`Edid::parse`] decodes the 128-byte base block and the CTA-861 and DisplayID extension
+//! blocks that follow it, as read into [`DisplayInfo::edid`](crate::DisplayInfo::edid).
+
+use thiserror::Error;
+
+pub use cta::{
+    Colorimetry, CtaExtension, HdmiForumVsdb, HdmiVsdb, HdrStaticMetadata, ShortAudioDescriptor,
+    ShortVideoDescriptor,
+};
+pub use display_id::{DisplayIdExtension, DisplayIdTiming, TiledTopology};
+
+mod cta;
+mod display_id;
+
+/// Size of the EDID base block and of every extension block.
+pub const EDID_BLOCK_SIZE: usize = 128;
+
+const EDID_HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum EdidError {
+    #[error("EDID truncated: expected {expected} bytes, got {actual}")]
+    Truncated { expected: usize, actual: usize },
+    #[error("EDID header is invalid")]
+    InvalidHeader,
+    #[error("EDID block {block} checksum mismatch")]
+    ChecksumMismatch { block: usize },
+}
+
+/// A detailed timing descriptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetailedTiming {
+    /// Pixel clock in kHz.
+    pub pixel_clock_khz: u32,
+    pub h_active: u16,
+    pub h_blank: u16,
+    pub v_active: u16,
+    pub v_blank: u16,
+    pub h_sync_offset: u16,
+    pub h_sync_width: u16,
+    pub v_sync_offset: u16,
+    pub v_sync_width: u16,
+    /// Horizontal image size in millimeters.
+    pub h_image_mm: u16,
+    /// Vertical image size in millimeters.
+    pub v_image_mm: u16,
+    pub h_border: u8,
+    pub v_border: u8,
+    pub interlaced: bool,
+}
+
+impl DetailedTiming {
+    /// Decode an 18-byte descriptor, `None` when it is a display descriptor instead.
+    pub(crate) fn parse(bytes: &[u8]) -> Option<DetailedTiming> {
+        let pixel_clock = u16::from_le_bytes([bytes[0], bytes[1]]);
+        if pixel_clock == 0 {
+            return None;
+        }
+
+        let hi = |byte: u8, shift: u8, mask: u8| (((byte & mask) as u16) >> shift) << 8;
+
+        Some(DetailedTiming {
+            pixel_clock_khz: pixel_clock as u32 * 10,
+            h_active: bytes[2] as u16 | hi(bytes[4], 4, 0xF0),
+            h_blank: bytes[3] as u16 | hi(bytes[4], 0, 0x0F),
+            v_active: bytes[5] as u16 | hi(bytes[7], 4, 0xF0),
+            v_blank: bytes[6] as u16 | hi(bytes[7], 0, 0x0F),
+            h_sync_offset: bytes[8] as u16 | hi(bytes[11], 6, 0xC0),
+            h_sync_width: bytes[9] as u16 | hi(bytes[11], 4, 0x30),
+            v_sync_offset: (bytes[10] >> 4) as u16 | ((bytes[11] as u16 & 0x0C) << 2),
+            v_sync_width: (bytes[10] & 0x0F) as u16 | ((bytes[11] as u16 & 0x03) << 4),
+            h_image_mm: bytes[12] as u16 | hi(bytes[14], 4, 0xF0),
+            v_image_mm: bytes[13] as u16 | hi(bytes[14], 0, 0x0F),
+            h_border: bytes[15],
+            v_border: bytes[16],
+            interlaced: bytes[17] & 0x80 != 0,
+        })
+    }
+
+    /// The refresh rate in Hz.
+    pub fn refresh_rate(&self) -> f32 {
+        let h_total = (self.h_active + self.h_blank) as f32;
+        let v_total = (self.v_active + self.v_blank) as f32;
+
+        if h_total == 0.0 || v_total == 0.0 {
+            return 0.0;
+        }
+
+        self.pixel_clock_khz as f32 * 1000.0 / (h_total * v_total)
+    }
+}
+
+/// An extension block following the base block.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EdidExtension {
+    Cta(CtaExtension),
+    DisplayId(DisplayIdExtension),
+    /// An extension block of another type, by tag.
+    Unknown(u8),
+}
+
+/// A decoded EDID.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edid {
+    /// Three-letter PNP manufacturer ID, e.g. `"DEL"`.
+    pub manufacturer_id: String,
+    pub product_code: u16,
+    pub serial_number: u32,
+    /// Week of manufacture, 1-54. `None` when unspecified or when the year is a model year.
+    pub manufacture_week: Option<u8>,
+    /// Year of manufacture, or model year when `is_model_year` is set.
+    pub manufacture_year: u16,
+    pub is_model_year: bool,
+    pub version: u8,
+    pub revision: u8,
+    /// Physical width in centimeters. This value may be 0.
+    pub width_cm: u8,
+    /// Physical height in centimeters. This value may be 0.
+    pub height_cm: u8,
+    /// Monitor name from the `0xFC` display descriptor.
+    pub monitor_name: Option<String>,
+    /// Monitor serial number from the `0xFF` display descriptor.
+    pub monitor_serial: Option<String>,
+    pub detailed_timings: Vec<DetailedTiming>,
+    pub extensions: Vec<EdidExtension>,
+}
+
+impl Edid {
+    pub fn parse(bytes: &[u8]) -> Result<Edid, EdidError> {
+        let block = get_block(bytes, 0)?;
+
+        if block[..8] != EDID_HEADER {
+            return Err(EdidError::InvalidHeader);
+        }
+
+        let mut monitor_name = None;
+        let mut monitor_serial = None;
+        let mut detailed_timings = Vec::new();
+
+        for descriptor in block[54..126].chunks_exact(18) {
+            if let Some(detailed_timing) = DetailedTiming::parse(descriptor) {
+                detailed_timings.push(detailed_timing);
+                continue;
+            }
+
+            match descriptor[3] {
+                0xFC => monitor_name = parse_descriptor_text(descriptor),
+                0xFF => monitor_serial = parse_descriptor_text(descriptor),
+                _ => {}
+            }
+        }
+
+        let extensions = (1..=block[126] as usize)
+            .map(|index| {
+                let block = get_block(bytes, index)?;
+
+                Ok(match block[0] {
+                    cta::CTA_EXTENSION_TAG => EdidExtension::Cta(CtaExtension::parse(block)),
+                    display_id::DISPLAY_ID_EXTENSION_TAG => {
+                        EdidExtension::DisplayId(DisplayIdExtension::parse(block))
+                    }
+                    tag => EdidExtension::Unknown(tag),
+                })
+            })
+            .collect::<Result<Vec<EdidExtension>, EdidError>>()?;
+
+        let week = block[16];
+        let is_model_year = week == 0xFF;
+
+        Ok(Edid {
+            manufacturer_id: parse_manufacturer_id(block[8], block[9]),
+            product_code: u16::from_le_bytes([block[10], block[11]]),
+            serial_number: u32::from_le_bytes([block[12], block[13], block[14], block[15]]),
+            manufacture_week: (week != 0 && !is_model_year).then_some(week),
+            manufacture_year: 1990 + block[17] as u16,
+            is_model_year,
+            version: block[18],
+            revision: block[19],
+            width_cm: block[21],
+            height_cm: block[22],
+            monitor_name,
+            monitor_serial,
+            detailed_timings,
+            extensions,
+        })
+    }
+
+    /// The first CTA-861 extension block.
+    pub fn cta(&self) -> Option<&CtaExtension> {
+        self.extensions
+            .iter()
+            .find_map(|extension| match extension {
+                EdidExtension::Cta(cta) => Some(cta),
+                _ => None,
+            })
+    }
+
+    /// The first DisplayID extension block.
+    pub fn display_id(&self) -> Option<&DisplayIdExtension> {
+        self.extensions
+            .iter()
+            .find_map(|extension| match extension {
+                EdidExtension::DisplayId(display_id) => Some(display_id),
+                _ => None,
+            })
+    }
+}
+
+/// Parse a raw EDID as read by a backend, logging instead of failing when it is malformed.
+pub(crate) fn parse_raw(edid: Option<&[u8]>) -> Option<Edid> {
+    Edid::parse(edid?)
+        .map_err(|err| log::info!("Parse EDID failed: {}", err))
+        .ok()
+}
+
+/// Get the checksummed `index`th block of `bytes`.
+pub(crate) fn get_block(bytes: &[u8], index: usize) -> Result<&[u8], EdidError> {
+    let start = index * EDID_BLOCK_SIZE;
+    let end = start + EDID_BLOCK_SIZE;
+
+    let block = bytes.get(start..end).ok_or(EdidError::Truncated {
+        expected: end,
+        actual: bytes.len(),
+    })?;
+
+    if block.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte)) != 0 {
+        return Err(EdidError::ChecksumMismatch { block: index });
+    }
+
+    Ok(block)
+}
+
+fn parse_manufacturer_id(hi: u8, lo: u8) -> String {
+    let id = u16::from_be_bytes([hi, lo]);
+
+    [10, 5, 0]
+        .iter()
+        .map(|shift| {
+            let letter = ((id >> shift) & 0x1F) as u8;
+            if (1..=26).contains(&letter) {
+                (b'A' + letter - 1) as char
+            } else {
+                '?'
+            }
+        })
+        .collect()
+}
+
+fn parse_descriptor_text(descriptor: &[u8]) -> Option<String> {
+    let text = &descriptor[5..18];
+    let end = text.iter().position(|&b| b == 0x0A).unwrap_or(text.len());
+    let text = String::from_utf8_lossy(&text[..end]).trim().to_string();
+
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &[u8] = include_bytes!("../../tests/fixtures/edid/base.bin");
+    const MODEL_YEAR: &[u8] = include_bytes!("../../tests/fixtures/edid/model_year.bin");
+    const TRUNCATED: &[u8] = include_bytes!("../../tests/fixtures/edid/truncated.bin");
+
+    #[test]
+    fn parse_base_block() {
+        let edid = Edid::parse(BASE).unwrap();
+
+        assert_eq!(edid.manufacturer_id, "GSM");
+        assert_eq!(edid.product_code, 0x5B09);
+        assert_eq!(edid.serial_number, 0x0001E4A3);
+        assert_eq!(edid.manufacture_week, Some(12));
+        assert_eq!(edid.manufacture_year, 2019);
+        assert!(!edid.is_model_year);
+        assert_eq!((edid.version, edid.revision), (1, 4));
+        assert_eq!((edid.width_cm, edid.height_cm), (53, 30));
+        assert_eq!(edid.monitor_name.as_deref(), Some("LG FHD"));
+        assert_eq!(edid.monitor_serial.as_deref(), Some("903NTPC1A234"));
+        assert!(edid.extensions.is_empty());
+
+        let [timing] = edid.detailed_timings[..] else {
+            panic!(
+                "expected one detailed timing, got {:?}",
+                edid.detailed_timings
+            );
+        };
+        assert_eq!((timing.h_active, timing.v_active), (1920, 1080));
+        assert_eq!((timing.h_image_mm, timing.v_image_mm), (527, 296));
+        assert_eq!(timing.pixel_clock_khz, 148500);
+        assert!((timing.refresh_rate() - 60.0).abs() < 0.01);
+        assert!(!timing.interlaced);
+    }
+
+    #[test]
+    fn parse_checksum_mismatch() {
+        let mut bytes = BASE.to_vec();
+        bytes[20] ^= 0x01;
+
+        assert_eq!(
+            Edid::parse(&bytes),
+            Err(EdidError::ChecksumMismatch { block: 0 })
+        );
+    }
+
+    #[test]
+    fn parse_truncated_extensions() {
+        assert_eq!(TRUNCATED[126], 1);
+        assert_eq!(
+            Edid::parse(TRUNCATED),
+            Err(EdidError::Truncated {
+                expected: 256,
+                actual: 128
+            })
+        );
+        assert_eq!(
+            Edid::parse(&BASE[..100]),
+            Err(EdidError::Truncated {
+                expected: 128,
+                actual: 100
+            })
+        );
+    }
+
+    #[test]
+    fn parse_invalid_header() {
+        let mut bytes = BASE.to_vec();
+        bytes[0] = 0x01;
+        bytes[127] = bytes[127].wrapping_sub(1);
+
+        assert_eq!(Edid::parse(&bytes), Err(EdidError::InvalidHeader));
+    }
+
+    #[test]
+    fn parse_model_year() {
+        let edid = Edid::parse(MODEL_YEAR).unwrap();
+
+        assert_eq!(edid.manufacturer_id, "SAM");
+        assert_eq!(edid.manufacture_week, None);
+        assert_eq!(edid.manufacture_year, 2023);
+        assert!(edid.is_model_year);
+        assert_eq!(edid.serial_number, 0);
+        assert_eq!(edid.monitor_serial.as_deref(), Some("H4ZN500123"));
+    }
+}
@@ -6,12 +6,26 @@ pub enum DIError {
     #[error("{0}")]
     Error(String),
     #[error(transparent)]
+    EdidError(#[from] crate::edid::EdidError),
+    #[error("{0} is not supported by this backend")]
+    Unsupported(&'static str),
+    #[error("Rotation {0} is not one of 0, 90, 180 or 270 degrees")]
+    InvalidRotation(f32),
+    #[error("Mode {0} is not supported by the output")]
+    InvalidMode(u32),
+    #[error("Rotation {0:?} is not supported by the CRTC")]
+    UnsupportedRotation(xcb::randr::Rotation),
+    #[error("RandR configuration failed: {0:?}")]
+    RandrConfigFailed(xcb::randr::SetConfig),
+    #[error(transparent)]
     StdStrUtf8Error(#[from] std::str::Utf8Error),
     #[error(transparent)]
     XcbError(#[from] xcb::Error),
     #[error(transparent)]
     XcbConnError(#[from] xcb::ConnError),
     #[error(transparent)]
+    XcbProtocolError(#[from] xcb::ProtocolError),
+    #[error(transparent)]
     SmithayClientToolkitClientDispatchError(
         #[from] smithay_client_toolkit::reexports::client::DispatchError,
     ),
@@ -19,6 +33,10 @@ pub enum DIError {
     SmithayClientToolkitClientConnectError(
         #[from] smithay_client_toolkit::reexports::client::ConnectError,
     ),
+    #[error(transparent)]
+    SmithayClientToolkitClientWaylandError(
+        #[from] smithay_client_toolkit::reexports::client::backend::WaylandError,
+    ),
 }
 
 #[cfg(target_os = "macos")]
@@ -26,6 +44,12 @@ pub enum DIError {
 pub enum DIError {
     #[error("{0}")]
     Error(String),
+    #[error(transparent)]
+    EdidError(#[from] crate::edid::EdidError),
+    #[error("{0} is not supported by this backend")]
+    Unsupported(&'static str),
+    #[error("Rotation {0} is not one of 0, 90, 180 or 270 degrees")]
+    InvalidRotation(f32),
 }
 
 #[cfg(target_os = "windows")]
@@ -34,6 +58,12 @@ pub enum DIError {
     #[error("{0}")]
     Error(String),
     #[error(transparent)]
+    EdidError(#[from] crate::edid::EdidError),
+    #[error("{0} is not supported by this backend")]
+    Unsupported(&'static str),
+    #[error("Rotation {0} is not one of 0, 90, 180 or 270 degrees")]
+    InvalidRotation(f32),
+    #[error(transparent)]
     WindowsCoreError(#[from] windows::core::Error),
     #[error(transparent)]
     Utf16Error(#[from] widestring::error::Utf16Error),
@@ -0,0 +1,360 @@
+//! Points and rectangles in the global display coordinate space.
+//!
+//! Rectangles are half-open: they contain their left and top edges but not their right and
+//! bottom edges, so a point on the border between two adjacent displays belongs to exactly one
+//! of them.
+//!
+//! Edges are computed in 64 bits, so rectangles reaching past `i32::MAX` don't overflow.
+
+/// A point, `x` pixels right and `y` pixels down from the origin.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Self {
+        Point { x, y }
+    }
+
+    /// The euclidean distance to `other`.
+    ///
+    /// ```
+    /// use display_info::geometry::Point;
+    ///
+    /// assert_eq!(Point::new(0, 0).distance(Point::new(3, 4)), 5.0);
+    /// ```
+    pub fn distance(&self, other: Point) -> f64 {
+        let dx = other.x as f64 - self.x as f64;
+        let dy = other.y as f64 - self.y as f64;
+
+        dx.hypot(dy)
+    }
+}
+
+/// A size in pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Size {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Size {
+    pub fn new(width: u32, height: u32) -> Self {
+        Size { width, height }
+    }
+}
+
+/// A rectangle, `width` pixels wide to the right of `x` and `height` pixels high below `y`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn from_origin_size(origin: Point, size: Size) -> Self {
+        Rect::new(origin.x, origin.y, size.width, size.height)
+    }
+
+    /// The rectangle between the `left` and `right`, `top` and `bottom` edges, empty when
+    /// they are swapped.
+    pub fn from_edges(left: i32, top: i32, right: i32, bottom: i32) -> Self {
+        Rect::from_wide_edges(left, top, right as i64, bottom as i64)
+    }
+
+    /// Like [`Rect::from_edges`] with the right and bottom edges in 64 bits, clamping the size
+    /// to `u32::MAX`.
+    fn from_wide_edges(left: i32, top: i32, right: i64, bottom: i64) -> Self {
+        Rect {
+            x: left,
+            y: top,
+            width: (right - left as i64).clamp(0, u32::MAX as i64) as u32,
+            height: (bottom - top as i64).clamp(0, u32::MAX as i64) as u32,
+        }
+    }
+
+    /// The x coordinate just past the right edge, in 64 bits.
+    fn wide_right(&self) -> i64 {
+        self.x as i64 + self.width as i64
+    }
+
+    /// The y coordinate just past the bottom edge, in 64 bits.
+    fn wide_bottom(&self) -> i64 {
+        self.y as i64 + self.height as i64
+    }
+
+    pub fn origin(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+
+    pub fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+
+    /// The x coordinate just past the right edge, saturating at `i32::MAX`.
+    ///
+    /// ```
+    /// use display_info::geometry::Rect;
+    ///
+    /// assert_eq!(Rect::new(-1920, 0, 1920, 1080).right(), 0);
+    /// assert_eq!(Rect::new(i32::MAX - 5, 0, 100, 100).right(), i32::MAX);
+    /// ```
+    pub fn right(&self) -> i32 {
+        saturate(self.wide_right())
+    }
+
+    /// The y coordinate just past the bottom edge, saturating at `i32::MAX`.
+    pub fn bottom(&self) -> i32 {
+        saturate(self.wide_bottom())
+    }
+
+    pub fn center(&self) -> Point {
+        Point::new(
+            saturate(self.x as i64 + (self.width / 2) as i64),
+            saturate(self.y as i64 + (self.height / 2) as i64),
+        )
+    }
+
+    pub fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Whether `point` is inside the rectangle.
+    ///
+    /// ```
+    /// use display_info::geometry::{Point, Rect};
+    ///
+    /// let rect = Rect::new(0, 0, 1920, 1080);
+    /// assert!(rect.contains(Point::new(0, 0)));
+    /// assert!(rect.contains(Point::new(1919, 1079)));
+    /// assert!(!rect.contains(Point::new(1920, 500)));
+    /// assert!(!rect.contains(Point::new(500, 1080)));
+    /// assert!(!rect.contains(Point::new(-1, 500)));
+    /// ```
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= self.x
+            && (point.x as i64) < self.wide_right()
+            && point.y >= self.y
+            && (point.y as i64) < self.wide_bottom()
+    }
+
+    /// Whether `other` lies entirely inside the rectangle.
+    ///
+    /// ```
+    /// use display_info::geometry::Rect;
+    ///
+    /// let rect = Rect::new(0, 0, 1920, 1080);
+    /// assert!(rect.contains_rect(&Rect::new(0, 0, 1920, 1080)));
+    /// assert!(rect.contains_rect(&Rect::new(100, 100, 200, 200)));
+    /// assert!(!rect.contains_rect(&Rect::new(1800, 100, 200, 200)));
+    /// ```
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.x >= self.x
+            && other.wide_right() <= self.wide_right()
+            && other.y >= self.y
+            && other.wide_bottom() <= self.wide_bottom()
+    }
+
+    /// Whether the rectangles overlap. Rectangles that only share an edge don't.
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The overlapping part of both rectangles, `None` when they don't overlap.
+    ///
+    /// ```
+    /// use display_info::geometry::Rect;
+    ///
+    /// let left = Rect::new(0, 0, 1920, 1080);
+    /// let right = Rect::new(1920, 0, 1920, 1080);
+    /// let window = Rect::new(1820, 100, 200, 100);
+    ///
+    /// assert_eq!(left.intersection(&right), None);
+    /// assert_eq!(left.intersection(&window), Some(Rect::new(1820, 100, 100, 100)));
+    /// assert_eq!(right.intersection(&window), Some(Rect::new(1920, 100, 100, 100)));
+    /// ```
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let rect = Rect::from_wide_edges(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.wide_right().min(other.wide_right()),
+            self.wide_bottom().min(other.wide_bottom()),
+        );
+
+        (!rect.is_empty()).then_some(rect)
+    }
+
+    /// The smallest rectangle containing both rectangles. Empty rectangles are ignored.
+    ///
+    /// ```
+    /// use display_info::geometry::Rect;
+    ///
+    /// let left = Rect::new(-1080, -200, 1080, 1920);
+    /// let right = Rect::new(0, 0, 2560, 1440);
+    ///
+    /// assert_eq!(left.union(&right), Rect::new(-1080, -200, 3640, 1920));
+    /// assert_eq!(left.union(&Rect::default()), left);
+    /// ```
+    pub fn union(&self, other: &Rect) -> Rect {
+        if other.is_empty() {
+            return *self;
+        }
+        if self.is_empty() {
+            return *other;
+        }
+
+        Rect::from_wide_edges(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.wide_right().max(other.wide_right()),
+            self.wide_bottom().max(other.wide_bottom()),
+        )
+    }
+
+    /// The distance from `point` to the closest pixel of the rectangle, 0 when it is inside.
+    ///
+    /// ```
+    /// use display_info::geometry::{Point, Rect};
+    ///
+    /// let rect = Rect::new(0, 0, 1920, 1080);
+    /// assert_eq!(rect.distance(Point::new(100, 100)), 0.0);
+    /// assert_eq!(rect.distance(Point::new(1920, 100)), 1.0);
+    /// assert_eq!(rect.distance(Point::new(-3, -4)), 5.0);
+    /// ```
+    pub fn distance(&self, point: Point) -> f64 {
+        if self.is_empty() {
+            return point.distance(self.origin());
+        }
+
+        let dx = axis_gap(
+            self.x,
+            self.wide_right(),
+            point.x as i64,
+            point.x as i64 + 1,
+        );
+        let dy = axis_gap(
+            self.y,
+            self.wide_bottom(),
+            point.y as i64,
+            point.y as i64 + 1,
+        );
+
+        (dx as f64).hypot(dy as f64)
+    }
+
+    /// The distance between the closest pixels of both rectangles, 0 when they overlap.
+    ///
+    /// ```
+    /// use display_info::geometry::Rect;
+    ///
+    /// let display = Rect::new(0, 0, 1920, 1080);
+    /// assert_eq!(display.distance_to_rect(&Rect::new(1900, 0, 100, 100)), 0.0);
+    /// assert_eq!(display.distance_to_rect(&Rect::new(1923, 0, 100, 100)), 4.0);
+    /// assert_eq!(display.distance_to_rect(&Rect::new(-100, -100, 98, 97)), 5.0);
+    /// ```
+    pub fn distance_to_rect(&self, other: &Rect) -> f64 {
+        if other.is_empty() {
+            return self.distance(other.origin());
+        }
+        if self.is_empty() {
+            return other.distance(self.origin());
+        }
+
+        let dx = axis_gap(
+            self.x,
+            self.wide_right(),
+            other.x as i64,
+            other.wide_right(),
+        );
+        let dy = axis_gap(
+            self.y,
+            self.wide_bottom(),
+            other.y as i64,
+            other.wide_bottom(),
+        );
+
+        (dx as f64).hypot(dy as f64)
+    }
+}
+
+fn saturate(value: i64) -> i32 {
+    value.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+/// The distance between the closest pixels of the non-empty spans `start..end` and
+/// `other_start..other_end` along one axis, 0 when they overlap.
+fn axis_gap(start: i32, end: i64, other_start: i64, other_end: i64) -> i64 {
+    (other_start - (end - 1))
+        .max(start as i64 - (other_end - 1))
+        .max(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edges_saturate_past_i32_max() {
+        let rect = Rect::new(i32::MAX - 5, i32::MAX - 5, 100, u32::MAX);
+
+        assert_eq!(rect.right(), i32::MAX);
+        assert_eq!(rect.bottom(), i32::MAX);
+        assert_eq!(rect.center(), Point::new(i32::MAX, i32::MAX));
+        assert!(rect.contains(Point::new(i32::MAX, i32::MAX)));
+    }
+
+    #[test]
+    fn max_width_is_not_negative() {
+        let rect = Rect::new(0, 0, u32::MAX, 1);
+
+        assert!(rect.contains(Point::new(i32::MAX, 0)));
+        assert!(!rect.contains(Point::new(-1, 0)));
+        assert_eq!(rect.distance(Point::new(-3, 0)), 3.0);
+        assert_eq!(
+            rect.intersection(&Rect::new(i32::MAX - 1, 0, 10, 1)),
+            Some(Rect::new(i32::MAX - 1, 0, 10, 1))
+        );
+    }
+
+    #[test]
+    fn union_and_from_edges_cover_the_whole_range() {
+        let left = Rect::new(i32::MIN, 0, 1, 1);
+        let right = Rect::new(i32::MAX - 1, 0, 1, 1);
+
+        assert_eq!(left.union(&right), Rect::new(i32::MIN, 0, u32::MAX, 1));
+        assert_eq!(
+            Rect::from_edges(i32::MIN, 0, i32::MAX, 1),
+            Rect::new(i32::MIN, 0, u32::MAX, 1)
+        );
+        assert_eq!(Rect::from_edges(10, 0, 5, 1), Rect::new(10, 0, 0, 1));
+    }
+
+    #[test]
+    fn distances_far_apart() {
+        let left = Rect::new(i32::MIN, 0, 1, 1);
+        let right = Rect::new(i32::MAX, 0, 1, 1);
+
+        assert_eq!(left.distance_to_rect(&right), u32::MAX as f64);
+        assert_eq!(right.distance(Point::new(i32::MIN, 0)), u32::MAX as f64);
+    }
+}
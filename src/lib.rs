@@ -1,6 +1,6 @@
 //! # example
 //! Get all display info
-//! ```
+//! ```no_run
 //! use display_info::DisplayInfo;
 //! use std::time::Instant;
 //!
@@ -15,8 +15,24 @@
 //! println!("运行耗时: {:?}", start.elapsed());
 //! ```
 
+pub mod backend;
+pub mod diff;
+pub mod edid;
 pub mod error;
-use error::{DIError, DIResult};
+pub mod geometry;
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(feature = "serde")]
+mod raw_handle;
+pub mod set;
+#[cfg(feature = "serde")]
+pub mod snapshot;
+#[cfg(all(feature = "async", target_family = "unix", not(target_os = "macos")))]
+pub mod stream;
+pub mod watcher;
+use edid::Edid;
+use error::DIResult;
+use geometry::{Point, Rect, Size};
 
 #[cfg(all(target_family = "unix", not(target_os = "macos")))]
 mod linux;
@@ -34,6 +50,7 @@ mod windows;
 use windows::ScreenRawHandle;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DisplayInfo {
     /// Unique identifier associated with the display.
     pub id: u32,
@@ -42,6 +59,7 @@ pub struct DisplayInfo {
     /// The display friendly name
     pub friendly_name: String,
     /// Native screen raw handle
+    #[cfg_attr(feature = "serde", serde(with = "raw_handle"))]
     pub raw_handle: ScreenRawHandle,
     /// The display x coordinate.
     pub x: i32,
@@ -63,17 +81,562 @@ pub struct DisplayInfo {
     pub frequency: f32,
     /// Whether the screen is the main screen
     pub is_primary: bool,
+    /// The raw EDID of the monitor, when the backend can read it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub edid: Option<Vec<u8>>,
+    /// HDR capabilities, from the HDR static metadata block of the EDID.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hdr: Option<HdrInfo>,
+    /// Identifier of the physical monitor that stays the same across sessions and backends.
+    /// Derived from the EDID manufacturer, product and serial, or the connector name without EDID.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stable_id: String,
+    /// All modes the display supports. Empty when the backend can't list them.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub modes: Vec<DisplayMode>,
+    /// The part of the display not covered by panels and docks, where windows can be placed.
+    /// The whole display when the backend can't tell.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub work_area: Rect,
+    /// The size of the part of the display not covered by panels and docks, when the backend
+    /// can only tell its size and not its position. Only set by the Wayland work area probe,
+    /// [`DisplayInfo::work_area`] is the whole display then.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub work_area_size: Option<Size>,
+}
+
+/// A display mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayMode {
+    /// Backend-specific mode identifier, the RandR mode XID on X11.
+    pub id: u32,
+    /// The mode pixel width.
+    pub width: u32,
+    /// The mode pixel height.
+    pub height: u32,
+    /// The mode refresh rate.
+    pub refresh_rate: f32,
+    /// Whether the display currently uses this mode.
+    pub is_current: bool,
+    /// Whether this is a preferred mode of the display.
+    pub is_preferred: bool,
+    pub is_interlaced: bool,
+    pub is_double_scan: bool,
+}
+
+/// Mirroring of the display content along its axes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Reflection {
+    /// Mirror along the x axis.
+    pub x: bool,
+    /// Mirror along the y axis.
+    pub y: bool,
+}
+
+/// Build [`DisplayInfo::stable_id`] from the monitor's EDID and the name of its connector.
+pub(crate) fn stable_id(edid: Option<&Edid>, connector: &str) -> String {
+    let Some(edid) = edid else {
+        return connector.to_string();
+    };
+
+    let serial = edid
+        .monitor_serial
+        .clone()
+        .or_else(|| (edid.serial_number != 0).then(|| format!("{:08X}", edid.serial_number)));
+
+    // identical monitors without a serial number can only be told apart by their connector
+    format!(
+        "{}-{:04X}-{}",
+        edid.manufacturer_id,
+        edid.product_code,
+        serial.as_deref().unwrap_or(connector)
+    )
+}
+
+/// Index of the display containing `point`, or the one picked by `fallback` when none does.
+pub(crate) fn find_from_point(
+    displays: &[DisplayInfo],
+    point: Point,
+    fallback: FromPointFallback,
+) -> Option<usize> {
+    let index = displays.iter().position(|d| d.bounds().contains(point));
+
+    match fallback {
+        _ if index.is_some() => index,
+        FromPointFallback::None => None,
+        FromPointFallback::Nearest => displays
+            .iter()
+            .enumerate()
+            .map(|(index, d)| (index, d, d.bounds().distance(point)))
+            .min_by(|(a_index, a, a_distance), (b_index, b, b_distance)| {
+                a_distance
+                    .total_cmp(b_distance)
+                    .then(b.is_primary.cmp(&a.is_primary))
+                    .then(a_index.cmp(b_index))
+            })
+            .map(|(index, _, _)| index),
+        FromPointFallback::Primary => displays.iter().position(|d| d.is_primary),
+    }
+}
+
+/// Index of the display a rectangle is on: the one with the largest overlap, or the closest one
+/// when it overlaps none. Ties go to the primary display, then to the first in `displays`.
+pub(crate) fn find_from_rect(displays: &[DisplayInfo], rect: &Rect) -> Option<usize> {
+    let rank = |d: &DisplayInfo| !d.is_primary;
+
+    let largest_overlap = displays
+        .iter()
+        .enumerate()
+        .filter_map(|(index, d)| Some((index, d, d.bounds().intersection(rect)?.area())))
+        .min_by_key(|&(index, d, area)| (std::cmp::Reverse(area), rank(d), index));
+
+    if let Some((index, _, _)) = largest_overlap {
+        return Some(index);
+    }
+
+    displays
+        .iter()
+        .enumerate()
+        .map(|(index, d)| (index, d, d.bounds().distance_to_rect(rect)))
+        .min_by(|(a_index, a, a_distance), (b_index, b, b_distance)| {
+            a_distance
+                .total_cmp(b_distance)
+                .then(rank(a).cmp(&rank(b)))
+                .then(a_index.cmp(b_index))
+        })
+        .map(|(index, _, _)| index)
+}
+
+/// What [`DisplayInfo::from_point_with`] returns for a point outside every display, e.g. in a
+/// gap between staggered monitors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FromPointFallback {
+    /// Fail, like [`DisplayInfo::from_point`].
+    #[default]
+    None,
+    /// The display closest to the point by edge distance, like `MONITOR_DEFAULTTONEAREST` on
+    /// Windows. Ties go to the primary display, then to the first enumerated display.
+    Nearest,
+    /// The primary display, like `MONITOR_DEFAULTTOPRIMARY` on Windows.
+    Primary,
+}
+
+/// HDR capabilities of a display.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HdrInfo {
+    /// Supports the traditional gamma SDR transfer function.
+    pub sdr: bool,
+    /// Supports the SMPTE ST 2084 (PQ) transfer function.
+    pub pq: bool,
+    /// Supports the Hybrid Log-Gamma transfer function.
+    pub hlg: bool,
+    /// Max luminance in cd/m².
+    pub max_luminance: Option<f32>,
+    /// Max frame-average luminance in cd/m².
+    pub max_frame_avg_luminance: Option<f32>,
+    /// Min luminance in cd/m².
+    pub min_luminance: Option<f32>,
+    /// Supports BT.2020 colorimetry (RGB or YCbCr).
+    pub bt2020: bool,
+}
+
+impl HdrInfo {
+    /// `None` when the EDID has no HDR static metadata block.
+    pub fn from_edid(edid: &Edid) -> Option<HdrInfo> {
+        let cta = edid.cta()?;
+        let metadata = cta.hdr_static_metadata?;
+        let bt2020 = cta
+            .colorimetry
+            .is_some_and(|c| c.bt2020_rgb || c.bt2020_ycc || c.bt2020_cycc);
+
+        Some(HdrInfo {
+            sdr: metadata.sdr,
+            pq: metadata.pq,
+            hlg: metadata.hlg,
+            max_luminance: metadata.max_luminance,
+            max_frame_avg_luminance: metadata.max_frame_avg_luminance,
+            min_luminance: metadata.min_luminance,
+            bt2020,
+        })
+    }
 }
 
 impl DisplayInfo {
+    /// The area of the display in the global coordinate space.
+    pub fn bounds(&self) -> Rect {
+        Rect::new(self.x, self.y, self.width, self.height)
+    }
+
+    pub fn all() -> DIResult<Vec<DisplayInfo>> {
+        backend::current_backend().get_all()
+    }
+
+    pub fn from_point(x: i32, y: i32) -> DIResult<DisplayInfo> {
+        backend::current_backend().get_from_point(x, y)
+    }
+
+    /// Get the display containing the point, or the display chosen by `fallback` when the point
+    /// is outside every display.
+    ///
+    /// ```no_run
+    /// use display_info::{DisplayInfo, FromPointFallback};
+    ///
+    /// let display_info = DisplayInfo::from_point_with(-20, 1500, FromPointFallback::Nearest).unwrap();
+    /// ```
+    pub fn from_point_with(x: i32, y: i32, fallback: FromPointFallback) -> DIResult<DisplayInfo> {
+        backend::current_backend().get_from_point_with(x, y, fallback)
+    }
+
+    /// Get the display a window at `x`, `y` of `width` by `height` pixels is on, like
+    /// `MonitorFromRect` with `MONITOR_DEFAULTTONEAREST` on Windows.
+    ///
+    /// That is the display with the largest intersection, the primary display and then the
+    /// first enumerated display winning ties. When the rectangle intersects no display, the
+    /// display closest to it is returned.
+    pub fn from_rect(x: i32, y: i32, width: u32, height: u32) -> DIResult<DisplayInfo> {
+        backend::current_backend().get_from_rect(Rect::new(x, y, width, height))
+    }
+
     pub fn from_name(name: impl ToString) -> DIResult<DisplayInfo> {
-        let name = name.to_string();
-        let display_infos = DisplayInfo::all()?;
+        backend::current_backend().get_from_name(&name.to_string())
+    }
 
-        display_infos
-            .iter()
-            .find(|&d| d.name == name)
-            .cloned()
-            .ok_or_else(|| DIError::new("Get display info failed"))
+    pub fn primary() -> DIResult<DisplayInfo> {
+        backend::current_backend().get_primary()
+    }
+
+    /// Switch the display to `mode`, one of its [`DisplayInfo::modes`].
+    pub fn set_mode(&self, mode: &DisplayMode) -> DIResult<()> {
+        backend::current_backend().set_mode(self, mode)
+    }
+
+    /// Rotate the display clock-wise by `rotation` degrees (0, 90, 180 or 270) and mirror it.
+    pub fn set_rotation(&self, rotation: f32, reflection: Reflection) -> DIResult<()> {
+        backend::current_backend().set_rotation(self, rotation, reflection)
+    }
+
+    /// Make the display the primary display.
+    pub fn set_primary(&self) -> DIResult<()> {
+        backend::current_backend().set_primary(self)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    #[cfg(all(target_family = "unix", not(target_os = "macos")))]
+    fn raw_handle(id: u32) -> ScreenRawHandle {
+        xcb::XidNew::new(id)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn raw_handle(id: u32) -> ScreenRawHandle {
+        id
+    }
+
+    #[cfg(target_os = "windows")]
+    fn raw_handle(id: u32) -> ScreenRawHandle {
+        ::windows::Win32::Graphics::Gdi::HMONITOR(id as usize as *mut _)
+    }
+
+    /// A display with the given id and bounds and defaults for everything else.
+    pub(crate) fn display(id: u32, x: i32, y: i32, width: u32, height: u32) -> DisplayInfo {
+        DisplayInfo {
+            id,
+            name: format!("DP-{id}"),
+            friendly_name: format!("DP-{id}"),
+            raw_handle: raw_handle(id),
+            x,
+            y,
+            width,
+            height,
+            width_mm: 0,
+            height_mm: 0,
+            rotation: 0.0,
+            scale_factor: 1.0,
+            frequency: 60.0,
+            is_primary: false,
+            edid: None,
+            hdr: None,
+            stable_id: format!("DP-{id}"),
+            modes: Vec::new(),
+            work_area: Rect::new(x, y, width, height),
+            work_area_size: None,
+        }
+    }
+
+    fn primary(mut display: DisplayInfo) -> DisplayInfo {
+        display.is_primary = true;
+        display
+    }
+
+    #[test]
+    fn stable_id_from_serial_string() {
+        let edid = Edid::parse(include_bytes!("../tests/fixtures/edid/base.bin")).unwrap();
+
+        assert_eq!(stable_id(Some(&edid), "HDMI-1"), "GSM-5B09-903NTPC1A234");
+        assert_eq!(stable_id(Some(&edid), "DP-2"), "GSM-5B09-903NTPC1A234");
+    }
+
+    #[test]
+    fn stable_id_from_serial_number() {
+        let mut edid = Edid::parse(include_bytes!("../tests/fixtures/edid/base.bin")).unwrap();
+        edid.monitor_serial = None;
+
+        assert_eq!(stable_id(Some(&edid), "HDMI-1"), "GSM-5B09-0001E4A3");
+    }
+
+    #[test]
+    fn stable_id_without_serial() {
+        let mut edid =
+            Edid::parse(include_bytes!("../tests/fixtures/edid/model_year.bin")).unwrap();
+        assert_eq!(edid.serial_number, 0);
+        edid.monitor_serial = None;
+
+        let id = format!("SAM-{:04X}-", edid.product_code);
+        assert_eq!(stable_id(Some(&edid), "HDMI-1"), id.clone() + "HDMI-1");
+        assert_eq!(stable_id(Some(&edid), "DP-2"), id + "DP-2");
+    }
+
+    #[test]
+    fn stable_id_without_edid() {
+        assert_eq!(stable_id(None, "HDMI-1"), "HDMI-1");
+        assert_eq!(stable_id(None, ""), "");
+    }
+
+    #[test]
+    fn from_point_on_border_goes_to_right_display() {
+        let displays = [
+            display(1, 0, 0, 1920, 1080),
+            display(2, 1920, 0, 1920, 1080),
+        ];
+
+        let find = |x, y| find_from_point(&displays, Point::new(x, y), FromPointFallback::None);
+
+        assert_eq!(find(1919, 500), Some(0));
+        assert_eq!(find(1920, 500), Some(1));
+        assert_eq!(find(3839, 1079), Some(1));
+        assert_eq!(find(3840, 500), None);
+        assert_eq!(find(1920, 1080), None);
+    }
+
+    #[test]
+    fn from_point_with_negative_origins() {
+        let displays = [
+            primary(display(1, 0, 0, 2560, 1440)),
+            display(2, -1080, -200, 1080, 1920),
+            display(3, 0, -1080, 1920, 1080),
+        ];
+
+        let find = |x, y| find_from_point(&displays, Point::new(x, y), FromPointFallback::None);
+
+        assert_eq!(find(-1, 0), Some(1));
+        assert_eq!(find(-1080, -200), Some(1));
+        assert_eq!(find(-1081, 0), None);
+        assert_eq!(find(-1, -201), None);
+        assert_eq!(find(0, -1), Some(2));
+        assert_eq!(find(0, 0), Some(0));
+    }
+
+    #[test]
+    fn from_point_in_gap() {
+        let displays = [
+            display(1, 0, 0, 1920, 1080),
+            display(2, 2000, 0, 1920, 1080),
+        ];
+
+        assert_eq!(
+            find_from_point(&displays, Point::new(1950, 500), FromPointFallback::None),
+            None
+        );
+    }
+
+    /// Two displays side by side with the right one 400 pixels lower, leaving gaps above and
+    /// below.
+    fn staggered() -> [DisplayInfo; 2] {
+        [
+            display(1, 0, 0, 1920, 1080),
+            display(2, 1920, 400, 1920, 1080),
+        ]
+    }
+
+    #[test]
+    fn from_point_nearest_staggered() {
+        let displays = staggered();
+
+        let find = |x, y| find_from_point(&displays, Point::new(x, y), FromPointFallback::Nearest);
+
+        assert_eq!(find(100, 100), Some(0));
+        // above the right display, closer to it than to the left one
+        assert_eq!(find(3000, 300), Some(1));
+        // below the left display
+        assert_eq!(find(1000, 1200), Some(0));
+        assert_eq!(find(-500, 2000), Some(0));
+        assert_eq!(find(5000, 0), Some(1));
+    }
+
+    #[test]
+    fn from_point_nearest_ties() {
+        // 51 pixels from the last column of the left display and the first of the right one
+        let point = Point::new(1050, 500);
+
+        let displays = [
+            display(1, 0, 0, 1000, 1000),
+            display(2, 1101, 0, 1000, 1000),
+        ];
+        assert_eq!(
+            find_from_point(&displays, point, FromPointFallback::Nearest),
+            Some(0)
+        );
+
+        let displays = [
+            display(1, 0, 0, 1000, 1000),
+            primary(display(2, 1101, 0, 1000, 1000)),
+        ];
+        assert_eq!(
+            find_from_point(&displays, point, FromPointFallback::Nearest),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn from_point_primary_staggered() {
+        let [left, right] = staggered();
+        let displays = [left, primary(right)];
+
+        let find = |x, y| find_from_point(&displays, Point::new(x, y), FromPointFallback::Primary);
+
+        assert_eq!(find(100, 100), Some(0));
+        assert_eq!(find(100, 1200), Some(1));
+        assert_eq!(find(3000, 300), Some(1));
+    }
+
+    #[test]
+    fn from_point_without_primary() {
+        let displays = staggered();
+
+        assert_eq!(
+            find_from_point(&displays, Point::new(100, 1200), FromPointFallback::Primary),
+            None
+        );
+        assert_eq!(
+            find_from_point(&displays, Point::new(100, 1200), FromPointFallback::Nearest),
+            Some(0)
+        );
+        assert_eq!(
+            find_from_point(&[], Point::new(0, 0), FromPointFallback::Nearest),
+            None
+        );
+    }
+
+    #[test]
+    fn from_rect_largest_overlap() {
+        let displays = [
+            display(1, 0, 0, 1920, 1080),
+            primary(display(2, 1920, 0, 1920, 1080)),
+        ];
+
+        // 200 pixels wide on the left display, 600 on the right one
+        assert_eq!(
+            find_from_rect(&displays, &Rect::new(1720, 100, 800, 600)),
+            Some(1)
+        );
+        // the primary display doesn't win against a larger overlap
+        assert_eq!(
+            find_from_rect(&displays, &Rect::new(1320, 100, 800, 600)),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn from_rect_overlap_ties() {
+        // 400 pixels wide on each display
+        let rect = Rect::new(1520, 100, 800, 600);
+
+        let displays = [
+            display(1, 0, 0, 1920, 1080),
+            display(2, 1920, 0, 1920, 1080),
+        ];
+        assert_eq!(find_from_rect(&displays, &rect), Some(0));
+
+        let displays = [
+            display(1, 0, 0, 1920, 1080),
+            primary(display(2, 1920, 0, 1920, 1080)),
+        ];
+        assert_eq!(find_from_rect(&displays, &rect), Some(1));
+    }
+
+    #[test]
+    fn from_rect_in_gap() {
+        let displays = staggered();
+
+        // above the right display, closer to it than to the left one
+        assert_eq!(
+            find_from_rect(&displays, &Rect::new(2500, 100, 200, 200)),
+            Some(1)
+        );
+        // below the left display
+        assert_eq!(
+            find_from_rect(&displays, &Rect::new(500, 1200, 200, 200)),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn from_rect_outside_every_display() {
+        let displays = staggered();
+
+        assert_eq!(
+            find_from_rect(&displays, &Rect::new(-3000, -3000, 100, 100)),
+            Some(0)
+        );
+        assert_eq!(
+            find_from_rect(&displays, &Rect::new(5000, 1000, 100, 100)),
+            Some(1)
+        );
+        assert_eq!(find_from_rect(&[], &Rect::new(0, 0, 100, 100)), None);
+    }
+
+    #[test]
+    fn from_rect_nearest_ties() {
+        // 51 pixels from the closest column of either display
+        let rect = Rect::new(1050, 0, 1, 100);
+
+        let displays = [
+            display(1, 0, 0, 1000, 1000),
+            display(2, 1101, 0, 1000, 1000),
+        ];
+        assert_eq!(find_from_rect(&displays, &rect), Some(0));
+
+        let displays = [
+            display(1, 0, 0, 1000, 1000),
+            primary(display(2, 1101, 0, 1000, 1000)),
+        ];
+        assert_eq!(find_from_rect(&displays, &rect), Some(1));
+    }
+
+    #[test]
+    fn from_rect_near_coordinate_limits() {
+        let displays = [
+            display(1, 0, 0, 1920, 1080),
+            display(2, i32::MAX - 1919, 0, 1920, 1080),
+        ];
+
+        assert_eq!(
+            find_from_rect(&displays, &Rect::new(i32::MAX - 5, 0, 100, 100)),
+            Some(1)
+        );
+        assert_eq!(
+            find_from_rect(&displays, &Rect::new(i32::MIN, 0, u32::MAX, 100)),
+            Some(0)
+        );
+        assert_eq!(
+            find_from_point(&displays, Point::new(i32::MAX, 0), FromPointFallback::None),
+            Some(1)
+        );
     }
 }
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use crate::{
+    DisplayInfo, DisplayMode, Reflection,
+    backend::DisplayBackend,
+    error::{DIError, DIResult},
+    watcher::DisplayEventSource,
+};
+
+use super::{WaylandBackend, XorgBackend, is_wayland, wayland::WaylandContext, xorg::XorgContext};
+
+/// A connection to the X server or Wayland compositor that is kept open across queries.
+///
+/// The backends open a new connection for every query. A context connects once, interns the X11
+/// atoms once and keeps the Wayland registry state, so repeated queries and configuration
+/// changes reuse them. Register it with [`set_backend`](crate::backend::set_backend)
+/// to have [`DisplayInfo`] answer from it.
+///
+/// ```no_run
+/// use display_info::{
+///     DisplayInfo,
+///     backend::{self, DisplayContext},
+/// };
+///
+/// backend::set_backend(DisplayContext::new().unwrap());
+///
+/// for _ in 0..100 {
+///     let display_infos = DisplayInfo::all().unwrap();
+/// }
+/// ```
+///
+/// Clones share the connection. Every [`DisplayWatcher`](crate::watcher::DisplayWatcher) and
+/// event stream of the context opens a connection of its own, so queries on the context never
+/// read its events.
+#[derive(Clone)]
+pub struct DisplayContext(Context);
+
+#[derive(Clone)]
+enum Context {
+    Xorg(Arc<XorgContext>),
+    Wayland(Arc<WaylandContext>),
+}
+
+impl DisplayContext {
+    /// Connect to the display server the platform backend would use.
+    pub fn new() -> DIResult<Self> {
+        if is_wayland() {
+            WaylandBackend::new().connect()
+        } else {
+            XorgBackend.connect()
+        }
+    }
+}
+
+impl From<XorgContext> for DisplayContext {
+    fn from(context: XorgContext) -> Self {
+        DisplayContext(Context::Xorg(Arc::new(context)))
+    }
+}
+
+impl From<WaylandContext> for DisplayContext {
+    fn from(context: WaylandContext) -> Self {
+        DisplayContext(Context::Wayland(Arc::new(context)))
+    }
+}
+
+impl DisplayBackend for DisplayContext {
+    fn name(&self) -> &str {
+        match &self.0 {
+            Context::Xorg(_) => "xorg",
+            Context::Wayland(_) => "wayland",
+        }
+    }
+
+    fn get_all(&self) -> DIResult<Vec<DisplayInfo>> {
+        match &self.0 {
+            Context::Xorg(context) => context.get_all(),
+            Context::Wayland(context) => context.get_all(),
+        }
+    }
+
+    fn set_mode(&self, display: &DisplayInfo, mode: &DisplayMode) -> DIResult<()> {
+        match &self.0 {
+            Context::Xorg(context) => context.set_mode(display, mode),
+            Context::Wayland(_) => Err(DIError::Unsupported("set_mode")),
+        }
+    }
+
+    fn set_rotation(
+        &self,
+        display: &DisplayInfo,
+        rotation: f32,
+        reflection: Reflection,
+    ) -> DIResult<()> {
+        match &self.0 {
+            Context::Xorg(context) => context.set_rotation(display, rotation, reflection),
+            Context::Wayland(_) => Err(DIError::Unsupported("set_rotation")),
+        }
+    }
+
+    fn set_primary(&self, display: &DisplayInfo) -> DIResult<()> {
+        match &self.0 {
+            Context::Xorg(context) => context.set_primary(display),
+            Context::Wayland(_) => Err(DIError::Unsupported("set_primary")),
+        }
+    }
+
+    fn watch(&self) -> DIResult<Box<dyn DisplayEventSource>> {
+        match &self.0 {
+            Context::Xorg(context) => context.watch(),
+            Context::Wayland(context) => context.watch(),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Kernel DRM connectors, for what the display server protocol doesn't expose.
+
+use std::fs;
+
+const DRM_SYSFS_PATH: &str = "/sys/class/drm";
+
+/// Read the EDID of the DRM connector named `connector`, e.g. `"DP-1"`.
+pub(super) fn get_edid(connector: &str) -> Option<Vec<u8>> {
+    fs::read_dir(DRM_SYSFS_PATH)
+        .ok()?
+        .flatten()
+        .find_map(|entry| {
+            // entries are named after their card, e.g. "card0-DP-1"
+            let file_name = entry.file_name();
+            let (card, name) = file_name.to_str()?.split_once('-')?;
+            if !card.starts_with("card") || name != connector {
+                return None;
+            }
+
+            let edid = fs::read(entry.path().join("edid")).ok()?;
+            (!edid.is_empty()).then_some(edid)
+        })
+}
@@ -1,13 +1,15 @@
 use std::env::var_os;
 
-pub use xorg::ScreenRawHandle;
-
-use crate::{DisplayInfo, error::DIResult};
+pub use context::DisplayContext;
+pub use wayland::WaylandBackend;
+pub use xorg::{ScreenRawHandle, XorgBackend};
 
+mod context;
+mod drm;
 mod wayland;
 mod xorg;
 
-fn is_wayland() -> bool {
+pub(crate) fn is_wayland() -> bool {
     var_os("WAYLAND_DISPLAY")
         .or(var_os("XDG_SESSION_TYPE"))
         .is_some_and(|v| {
@@ -17,21 +19,3 @@ fn is_wayland() -> bool {
                 .contains("wayland")
         })
 }
-
-impl DisplayInfo {
-    pub fn all() -> DIResult<Vec<DisplayInfo>> {
-        if is_wayland() {
-            wayland::get_all()
-        } else {
-            xorg::get_all()
-        }
-    }
-
-    pub fn from_point(x: i32, y: i32) -> DIResult<DisplayInfo> {
-        if is_wayland() {
-            wayland::get_from_point(x, y)
-        } else {
-            xorg::get_from_point(x, y)
-        }
-    }
-}
@@ -1,13 +1,33 @@
+use std::sync::{Mutex, PoisonError};
+
+use smithay_client_toolkit::compositor::{CompositorHandler, CompositorState};
 use smithay_client_toolkit::output::{OutputHandler, OutputInfo, OutputState};
-use smithay_client_toolkit::reexports::client::globals::registry_queue_init;
-use smithay_client_toolkit::reexports::client::protocol::wl_output;
-use smithay_client_toolkit::reexports::client::{Connection, QueueHandle};
+use smithay_client_toolkit::reexports::client::globals::{GlobalList, registry_queue_init};
+use smithay_client_toolkit::reexports::client::protocol::{wl_output, wl_surface};
+use smithay_client_toolkit::reexports::client::{Connection, EventQueue, QueueHandle};
 use smithay_client_toolkit::registry::{ProvidesRegistryState, RegistryState};
-use smithay_client_toolkit::{delegate_output, delegate_registry, registry_handlers};
+use smithay_client_toolkit::shell::WaylandSurface;
+use smithay_client_toolkit::shell::wlr_layer::{
+    Anchor, KeyboardInteractivity, Layer, LayerShell, LayerShellHandler, LayerSurface,
+    LayerSurfaceConfigure,
+};
+use smithay_client_toolkit::{
+    delegate_compositor, delegate_layer, delegate_output, delegate_registry, registry_handlers,
+};
 use xcb::XidNew;
 
-use crate::DisplayInfo;
 use crate::error::{DIError, DIResult};
+use crate::{
+    DisplayInfo, DisplayMode, HdrInfo,
+    backend::DisplayBackend,
+    diff::DisplayChange,
+    edid,
+    geometry::{Rect, Size},
+    stable_id,
+    watcher::{self, DisplayEvent, DisplayEventSource},
+};
+
+use super::{DisplayContext, drm};
 
 impl From<&OutputInfo> for DisplayInfo {
     fn from(info: &OutputInfo) -> Self {
@@ -24,9 +44,31 @@ impl From<&OutputInfo> for DisplayInfo {
             .find(|m| m.current || m.preferred)
             .map(|m| m.refresh_rate as f32 / 1000.0)
             .unwrap_or(0.);
+        let modes = info
+            .modes
+            .iter()
+            .enumerate()
+            .map(|(index, m)| DisplayMode {
+                id: index as u32,
+                width: m.dimensions.0 as u32,
+                height: m.dimensions.1 as u32,
+                refresh_rate: m.refresh_rate as f32 / 1000.0,
+                is_current: m.current,
+                is_preferred: m.preferred,
+                is_interlaced: false,
+                is_double_scan: false,
+            })
+            .collect();
         let (x, y) = info.logical_position.unwrap_or(info.location);
         let (w, h) = info.logical_size.unwrap_or(info.physical_size);
         let (width_mm, height_mm) = info.physical_size;
+        // wl_output doesn't carry the EDID, read it from the DRM connector of the same name
+        let edid = info.name.as_deref().and_then(drm::get_edid);
+        let parsed_edid = edid::parse_raw(edid.as_deref());
+        let x = ((x as f32) / scale_factor) as i32;
+        let y = ((y as f32) / scale_factor) as i32;
+        let width = ((w as f32) / scale_factor) as u32;
+        let height = ((h as f32) / scale_factor) as u32;
         DisplayInfo {
             id: info.id,
             name: info.name.clone().unwrap_or_default(),
@@ -34,25 +76,111 @@ impl From<&OutputInfo> for DisplayInfo {
                 .name
                 .clone()
                 .unwrap_or(format!("Unknown Display {}", info.id)),
-            raw_handle: unsafe { xcb::randr::Output::new(info.id) },
-            x: ((x as f32) / scale_factor) as i32,
-            y: ((y as f32) / scale_factor) as i32,
-            width: ((w as f32) / scale_factor) as u32,
-            height: ((h as f32) / scale_factor) as u32,
+            raw_handle: xcb::randr::Output::new(info.id),
+            x,
+            y,
+            width,
+            height,
             width_mm,
             height_mm,
             rotation,
             scale_factor,
             frequency,
             is_primary: false,
+            edid,
+            hdr: parsed_edid.as_ref().and_then(HdrInfo::from_edid),
+            stable_id: stable_id(
+                parsed_edid.as_ref(),
+                info.name.as_deref().unwrap_or_default(),
+            ),
+            modes,
+            work_area: Rect::new(x, y, width, height),
+            work_area_size: None,
         }
     }
 }
 
+/// Display information from the Wayland compositor's `wl_output` globals.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WaylandBackend {
+    probe_work_area: bool,
+}
+
+impl WaylandBackend {
+    pub fn new() -> Self {
+        WaylandBackend::default()
+    }
+
+    /// Find the [`DisplayInfo::work_area`] of every output with a `zwlr_layer_shell_v1` surface
+    /// that fills the area left by the exclusive zones of panels and docks.
+    ///
+    /// The compositor only reports the size of that area, so the work area keeps the origin of
+    /// the output: a panel at the top or left edge shrinks it without moving it.
+    /// Has no effect on compositors without the wlr layer shell.
+    pub fn probe_work_area(mut self, probe_work_area: bool) -> Self {
+        self.probe_work_area = probe_work_area;
+        self
+    }
+
+    /// Open a connection to the compositor that all queries of the context reuse.
+    pub fn connect(&self) -> DIResult<DisplayContext> {
+        Ok(DisplayContext::from(WaylandContext::connect(
+            self.probe_work_area,
+        )?))
+    }
+
+    /// Query over a connection the application already holds, e.g. the one of its windowing
+    /// library, instead of opening a new one.
+    ///
+    /// The outputs are followed on an event queue of their own, so the application's queues and
+    /// their state are left alone. Binding the registry and the outputs creates protocol objects
+    /// that live as long as the connection, so keep the context for all queries rather than
+    /// creating one per query.
+    ///
+    /// ```no_run
+    /// use display_info::backend::{DisplayBackend, WaylandBackend};
+    /// use smithay_client_toolkit::reexports::client::Connection;
+    ///
+    /// let conn = Connection::connect_to_env().unwrap();
+    /// let context = WaylandBackend::new().with_connection(&conn).unwrap();
+    ///
+    /// for _ in 0..100 {
+    ///     let display_infos = context.get_all().unwrap();
+    /// }
+    /// ```
+    pub fn with_connection(&self, conn: &Connection) -> DIResult<DisplayContext> {
+        Ok(DisplayContext::from(WaylandContext::new(
+            conn,
+            self.probe_work_area,
+        )?))
+    }
+}
+
 /// Application data.
 struct ListOutputs {
     registry_state: RegistryState,
     output_state: OutputState,
+    /// The outputs as last reported, to tell what an update changed.
+    displays: Vec<DisplayInfo>,
+    /// Changes since the last dispatch.
+    events: Vec<DisplayEvent>,
+    probes: Vec<WorkAreaProbe>,
+}
+
+/// A layer surface filling the area of an output left by exclusive zones.
+struct WorkAreaProbe {
+    layer_surface: LayerSurface,
+    output_id: u32,
+    /// Size of the area, once configured.
+    size: Option<(u32, u32)>,
+}
+
+impl ListOutputs {
+    fn get_display_info(&self, output: &wl_output::WlOutput) -> Option<DisplayInfo> {
+        self.output_state
+            .info(output)
+            .map(|info| DisplayInfo::from(&info))
+    }
 }
 
 impl OutputHandler for ListOutputs {
@@ -64,27 +192,127 @@ impl OutputHandler for ListOutputs {
         &mut self,
         _conn: &Connection,
         _qh: &QueueHandle<Self>,
-        _output: wl_output::WlOutput,
+        output: wl_output::WlOutput,
     ) {
+        if let Some(display_info) = self.get_display_info(&output) {
+            self.displays.push(display_info.clone());
+            self.events.push(DisplayEvent::Added(display_info));
+        }
     }
 
     fn update_output(
         &mut self,
         _conn: &Connection,
         _qh: &QueueHandle<Self>,
-        _output: wl_output::WlOutput,
+        output: wl_output::WlOutput,
     ) {
+        let Some(display_info) = self.get_display_info(&output) else {
+            return;
+        };
+
+        match self.displays.iter_mut().find(|d| d.id == display_info.id) {
+            Some(old_display_info) => {
+                if let Some(change) = DisplayChange::new(old_display_info, &display_info) {
+                    self.events.extend(watcher::get_display_events(&change));
+                }
+                *old_display_info = display_info;
+            }
+            None => {
+                self.displays.push(display_info.clone());
+                self.events.push(DisplayEvent::Added(display_info));
+            }
+        }
     }
 
     fn output_destroyed(
         &mut self,
         _conn: &Connection,
         _qh: &QueueHandle<Self>,
-        _output: wl_output::WlOutput,
+        output: wl_output::WlOutput,
     ) {
+        let Some(info) = self.output_state.info(&output) else {
+            return;
+        };
+
+        if let Some(index) = self.displays.iter().position(|d| d.id == info.id) {
+            let display_info = self.displays.remove(index);
+            self.events.push(DisplayEvent::Removed(display_info));
+        }
     }
 }
 
+impl CompositorHandler for ListOutputs {
+    fn scale_factor_changed(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _surface: &wl_surface::WlSurface,
+        _new_factor: i32,
+    ) {
+    }
+
+    fn transform_changed(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _surface: &wl_surface::WlSurface,
+        _new_transform: wl_output::Transform,
+    ) {
+    }
+
+    fn frame(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _surface: &wl_surface::WlSurface,
+        _time: u32,
+    ) {
+    }
+
+    fn surface_enter(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _surface: &wl_surface::WlSurface,
+        _output: &wl_output::WlOutput,
+    ) {
+    }
+
+    fn surface_leave(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _surface: &wl_surface::WlSurface,
+        _output: &wl_output::WlOutput,
+    ) {
+    }
+}
+
+impl LayerShellHandler for ListOutputs {
+    fn closed(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, layer: &LayerSurface) {
+        self.probes.retain(|probe| probe.layer_surface != *layer);
+    }
+
+    fn configure(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        layer: &LayerSurface,
+        configure: LayerSurfaceConfigure,
+        _serial: u32,
+    ) {
+        if let Some(probe) = self
+            .probes
+            .iter_mut()
+            .find(|probe| probe.layer_surface == *layer)
+        {
+            probe.size = Some(configure.new_size);
+        }
+    }
+}
+
+delegate_compositor!(ListOutputs);
+delegate_layer!(ListOutputs);
 delegate_output!(ListOutputs);
 delegate_registry!(ListOutputs);
 
@@ -98,47 +326,271 @@ impl ProvidesRegistryState for ListOutputs {
     }
 }
 
-pub fn get_all() -> DIResult<Vec<DisplayInfo>> {
-    let conn = Connection::connect_to_env()?;
+/// An event queue following the outputs of a connection.
+struct OutputQueue {
+    event_queue: EventQueue<ListOutputs>,
+    list_outputs: ListOutputs,
+    /// Bound when the work area is probed, `None` without the wlr layer shell.
+    work_area_shell: Option<(CompositorState, LayerShell)>,
+}
+
+impl OutputQueue {
+    fn new(conn: &Connection, probe_work_area: bool) -> DIResult<Self> {
+        let (globals, mut event_queue) = registry_queue_init(conn).map_err(DIError::new)?;
+        let qh = event_queue.handle();
 
-    let (globals, mut event_queue) = registry_queue_init(&conn).unwrap();
-    let qh = event_queue.handle();
+        let registry_state = RegistryState::new(&globals);
 
-    let registry_state = RegistryState::new(&globals);
+        let output_delegate = OutputState::new(&globals, &qh);
 
-    let output_delegate = OutputState::new(&globals, &qh);
+        let mut list_outputs = ListOutputs {
+            registry_state,
+            output_state: output_delegate,
+            displays: Vec::new(),
+            events: Vec::new(),
+            probes: Vec::new(),
+        };
 
-    let mut list_outputs = ListOutputs {
-        registry_state,
-        output_state: output_delegate,
-    };
+        event_queue.roundtrip(&mut list_outputs)?;
 
-    event_queue.roundtrip(&mut list_outputs)?;
+        let work_area_shell = probe_work_area
+            .then(|| bind_work_area_shell(&globals, &event_queue.handle()))
+            .flatten();
 
-    list_outputs
-        .output_state
-        .outputs()
-        .map(|output| {
-            list_outputs
-                .output_state
-                .info(&output)
-                .map(|o| DisplayInfo::from(&o))
-                .ok_or(DIError::new("Cannot get info from Output in Wayland"))
+        Ok(OutputQueue {
+            event_queue,
+            list_outputs,
+            work_area_shell,
         })
-        .collect::<DIResult<Vec<DisplayInfo>>>()
+    }
+
+    /// The outputs as of now, including changes since the last call.
+    fn get_all(&mut self) -> DIResult<Vec<DisplayInfo>> {
+        self.event_queue.roundtrip(&mut self.list_outputs)?;
+
+        if self.work_area_shell.is_some() {
+            self.probe_work_areas()?;
+        }
+
+        let list_outputs = &self.list_outputs;
+
+        list_outputs
+            .output_state
+            .outputs()
+            .map(|output| {
+                let mut display_info = list_outputs
+                    .output_state
+                    .info(&output)
+                    .map(|o| DisplayInfo::from(&o))
+                    .ok_or(DIError::new("Cannot get info from Output in Wayland"))?;
+
+                let probed_size = list_outputs
+                    .probes
+                    .iter()
+                    .find(|probe| probe.output_id == display_info.id)
+                    .and_then(|probe| probe.size)
+                    // 0 lets the client pick the size, the compositor didn't constrain it
+                    .filter(|&(width, height)| width != 0 && height != 0);
+
+                // the probe can't tell where the area is, e.g. below a top panel or right of a
+                // left one, so work_area stays the whole output
+                display_info.work_area_size = probed_size.map(|(width, height)| {
+                    let scale_factor = display_info.scale_factor;
+                    Size::new(
+                        ((width as f32) / scale_factor) as u32,
+                        ((height as f32) / scale_factor) as u32,
+                    )
+                });
+
+                Ok(display_info)
+            })
+            .collect::<DIResult<Vec<DisplayInfo>>>()
+    }
+
+    /// Create a probe surface on every output that has none yet, filling the space left by
+    /// exclusive zones. The probes stay and are reconfigured when the exclusive zones change.
+    fn probe_work_areas(&mut self) -> DIResult<()> {
+        let Some((compositor_state, layer_shell)) = &self.work_area_shell else {
+            return Ok(());
+        };
+
+        let qh = self.event_queue.handle();
+        let list_outputs = &mut self.list_outputs;
+
+        let mut has_new_probes = false;
+        for output in list_outputs.output_state.outputs() {
+            let Some(info) = list_outputs.output_state.info(&output) else {
+                continue;
+            };
+            if list_outputs
+                .probes
+                .iter()
+                .any(|probe| probe.output_id == info.id)
+            {
+                continue;
+            }
+
+            let surface = compositor_state.create_surface(&qh);
+            let layer_surface = layer_shell.create_layer_surface(
+                &qh,
+                surface,
+                Layer::Bottom,
+                Some("display-info-work-area"),
+                Some(&output),
+            );
+            layer_surface.set_anchor(Anchor::TOP | Anchor::BOTTOM | Anchor::LEFT | Anchor::RIGHT);
+            layer_surface.set_size(0, 0);
+            layer_surface.set_exclusive_zone(0);
+            layer_surface.set_keyboard_interactivity(KeyboardInteractivity::None);
+            // committing without a buffer asks for a configure, the surface is never mapped
+            layer_surface.commit();
+
+            list_outputs.probes.push(WorkAreaProbe {
+                layer_surface,
+                output_id: info.id,
+                size: None,
+            });
+            has_new_probes = true;
+        }
+
+        if has_new_probes {
+            self.event_queue.roundtrip(&mut self.list_outputs)?;
+        }
+
+        Ok(())
+    }
 }
 
-pub fn get_from_point(x: i32, y: i32) -> DIResult<DisplayInfo> {
-    let display_infos = get_all()?;
+fn bind_work_area_shell(
+    globals: &GlobalList,
+    qh: &QueueHandle<ListOutputs>,
+) -> Option<(CompositorState, LayerShell)> {
+    match (
+        CompositorState::bind(globals, qh),
+        LayerShell::bind(globals, qh),
+    ) {
+        (Ok(compositor_state), Ok(layer_shell)) => Some((compositor_state, layer_shell)),
+        (Err(err), _) | (_, Err(err)) => {
+            log::info!("Probe work area unavailable: {}", err);
+            None
+        }
+    }
+}
 
-    display_infos
-        .iter()
-        .find(|&d| {
-            x >= d.x
-                && x - (d.width as i32) < d.x + d.width as i32
-                && y >= d.y
-                && y - (d.height as i32) < d.y + d.height as i32
+/// A compositor connection with the output state of its last query.
+pub(super) struct WaylandContext {
+    /// Its event queue keeps the connection open.
+    outputs: Mutex<OutputQueue>,
+}
+
+impl WaylandContext {
+    fn connect(probe_work_area: bool) -> DIResult<Self> {
+        WaylandContext::new(&Connection::connect_to_env()?, probe_work_area)
+    }
+
+    fn new(conn: &Connection, probe_work_area: bool) -> DIResult<Self> {
+        let outputs = OutputQueue::new(conn, probe_work_area)?;
+
+        Ok(WaylandContext {
+            outputs: Mutex::new(outputs),
         })
-        .cloned()
-        .ok_or_else(|| DIError::new("Get display info failed"))
+    }
+
+    pub(super) fn get_all(&self) -> DIResult<Vec<DisplayInfo>> {
+        let mut outputs = self.outputs.lock().unwrap_or_else(PoisonError::into_inner);
+        let display_infos = outputs.get_all()?;
+
+        // nobody takes the changes of this queue, the watchers have their own
+        outputs.list_outputs.events.clear();
+
+        Ok(display_infos)
+    }
+
+    /// The watcher gets a connection of its own. Sharing the socket of the context, a query
+    /// could read the events of the watcher before it polls, and an async stream can't register
+    /// the same socket twice.
+    pub(super) fn watch(&self) -> DIResult<Box<dyn DisplayEventSource>> {
+        Ok(Box::new(WaylandEventSource::connect()?))
+    }
+}
+
+/// Output changes from an event queue kept running for the lifetime of the watcher, on a
+/// connection owned by the watcher.
+struct WaylandEventSource {
+    #[cfg_attr(not(feature = "async"), allow(dead_code))]
+    conn: Connection,
+    event_queue: EventQueue<ListOutputs>,
+    list_outputs: ListOutputs,
+}
+
+impl WaylandEventSource {
+    fn connect() -> DIResult<Self> {
+        let conn = Connection::connect_to_env()?;
+        let OutputQueue {
+            event_queue,
+            mut list_outputs,
+            ..
+        } = OutputQueue::new(&conn, false)?;
+
+        // the outputs present at startup are not changes
+        list_outputs.events.clear();
+
+        Ok(WaylandEventSource {
+            conn,
+            event_queue,
+            list_outputs,
+        })
+    }
+}
+
+impl DisplayEventSource for WaylandEventSource {
+    fn next_events(&mut self) -> DIResult<Vec<DisplayEvent>> {
+        while self.list_outputs.events.is_empty() {
+            self.event_queue.blocking_dispatch(&mut self.list_outputs)?;
+        }
+
+        Ok(std::mem::take(&mut self.list_outputs.events))
+    }
+
+    #[cfg(feature = "async")]
+    fn as_raw_fd(&self) -> Option<std::os::fd::RawFd> {
+        use std::os::fd::AsRawFd;
+
+        Some(self.conn.backend().poll_fd().as_raw_fd())
+    }
+
+    #[cfg(feature = "async")]
+    fn try_next_events(&mut self) -> DIResult<Vec<DisplayEvent>> {
+        use smithay_client_toolkit::reexports::client::backend::WaylandError;
+
+        self.event_queue.dispatch_pending(&mut self.list_outputs)?;
+        self.event_queue.flush()?;
+
+        // `None` means events are already queued, dispatched below
+        if let Some(guard) = self.event_queue.prepare_read() {
+            match guard.read() {
+                Ok(_) => {}
+                Err(WaylandError::Io(err)) if err.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        self.event_queue.dispatch_pending(&mut self.list_outputs)?;
+
+        Ok(std::mem::take(&mut self.list_outputs.events))
+    }
+}
+
+impl DisplayBackend for WaylandBackend {
+    fn name(&self) -> &str {
+        "wayland"
+    }
+
+    fn get_all(&self) -> DIResult<Vec<DisplayInfo>> {
+        WaylandContext::connect(self.probe_work_area)?.get_all()
+    }
+
+    fn watch(&self) -> DIResult<Box<dyn DisplayEventSource>> {
+        Ok(Box::new(WaylandEventSource::connect()?))
+    }
 }
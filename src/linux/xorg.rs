@@ -1,33 +1,182 @@
 use std::str;
 use xcb::x::{Atom, GetAtomName};
 use xcb::{
-    Connection, Xid,
+    Connection, Event, Extension, Xid, XidNew,
     randr::{
-        GetCrtcInfo, GetMonitors, GetOutputInfo, GetScreenResources, Mode, ModeFlag, ModeInfo,
-        Output, Rotation,
+        Crtc, GetCrtcInfo, GetCrtcInfoReply, GetMonitors, GetOutputInfo, GetOutputInfoReply,
+        GetOutputPrimary, GetOutputProperty, GetOutputPropertyReply, GetScreenResources,
+        GetScreenSizeRange, Mode, ModeFlag, ModeInfo, NotifyMask, Output, Rotation, SelectInput,
+        SetConfig, SetCrtcConfig, SetOutputPrimary, SetScreenSize,
+    },
+    x::{
+        ATOM_ANY, ATOM_CARDINAL, ATOM_NONE, ATOM_RESOURCE_MANAGER, ATOM_STRING, ATOM_WINDOW,
+        Drawable, GetGeometry, GetGeometryCookie, GetGeometryReply, GetProperty, GetPropertyCookie,
+        GetPropertyReply, Screen, Timestamp, Window,
     },
-    x::{ATOM_RESOURCE_MANAGER, ATOM_STRING, GetProperty, Screen},
 };
 
-use crate::DisplayInfo;
 use crate::error::{DIError, DIResult};
+use crate::{
+    DisplayInfo, DisplayMode, HdrInfo, Reflection,
+    backend::DisplayBackend,
+    diff::DisplayDiff,
+    edid,
+    geometry::{Rect, Size},
+    stable_id,
+    watcher::{self, DisplayEvent, DisplayEventSource},
+};
+
+use super::DisplayContext;
 
 pub type ScreenRawHandle = Output;
 
-fn get_name(conn: &Connection, atom: Atom) -> DIResult<String> {
-    let get_atom_value = conn.send_request(&GetAtomName { atom });
+/// Display information from the X server through RandR.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct XorgBackend;
 
-    let get_atom_value_reply = conn.wait_for_reply(get_atom_value)?;
-    Ok(get_atom_value_reply.name().to_string())
+impl XorgBackend {
+    /// Open a connection to the X server that all queries of the context reuse.
+    pub fn connect(&self) -> DIResult<DisplayContext> {
+        Ok(DisplayContext::from(XorgContext::connect()?))
+    }
 }
 
-// per https://gitlab.freedesktop.org/xorg/app/xrandr/-/blob/master/xrandr.c#L576
-fn get_current_frequency(mode_infos: &[ModeInfo], mode: Mode) -> f32 {
-    let mode_info = match mode_infos.iter().find(|m| m.id == mode.resource_id()) {
-        Some(mode_info) => mode_info,
-        None => return 0.0,
-    };
+xcb::atoms_struct! {
+    /// Interned even when they don't exist yet, so they stay valid when a window manager starts
+    /// after the connection was opened.
+    #[derive(Debug, Clone, Copy)]
+    struct Atoms {
+        edid => b"EDID" only_if_exists = false,
+        net_client_list => b"_NET_CLIENT_LIST" only_if_exists = false,
+        net_wm_strut_partial => b"_NET_WM_STRUT_PARTIAL" only_if_exists = false,
+        net_wm_strut => b"_NET_WM_STRUT" only_if_exists = false,
+        net_workarea => b"_NET_WORKAREA" only_if_exists = false,
+        net_current_desktop => b"_NET_CURRENT_DESKTOP" only_if_exists = false,
+    }
+}
+
+/// An X server connection with the atoms the queries need.
+pub(super) struct XorgContext {
+    conn: Connection,
+    screen_index: i32,
+    atoms: Atoms,
+}
+
+impl XorgContext {
+    fn connect() -> DIResult<Self> {
+        // RandR events are only parsed with the extension listed
+        let (conn, screen_index) =
+            Connection::connect_with_extensions(None, &[], &[Extension::RandR])?;
+        let atoms = Atoms::intern_all(&conn)?;
+
+        Ok(XorgContext {
+            conn,
+            screen_index,
+            atoms,
+        })
+    }
+
+    fn screen(&self) -> DIResult<&Screen> {
+        get_screen(&self.conn, self.screen_index)
+    }
+
+    pub(super) fn get_all(&self) -> DIResult<Vec<DisplayInfo>> {
+        get_all(&self.conn, self.screen()?, &self.atoms)
+    }
 
+    pub(super) fn set_mode(&self, display: &DisplayInfo, mode: &DisplayMode) -> DIResult<()> {
+        set_crtc_config(
+            &self.conn,
+            self.screen()?,
+            display.raw_handle,
+            Some(mode.id),
+            None,
+        )
+    }
+
+    pub(super) fn set_rotation(
+        &self,
+        display: &DisplayInfo,
+        rotation: f32,
+        reflection: Reflection,
+    ) -> DIResult<()> {
+        let rotation = get_randr_rotation(rotation, reflection)?;
+
+        set_crtc_config(
+            &self.conn,
+            self.screen()?,
+            display.raw_handle,
+            None,
+            Some(rotation),
+        )
+    }
+
+    pub(super) fn set_primary(&self, display: &DisplayInfo) -> DIResult<()> {
+        set_primary(&self.conn, self.screen()?, display.raw_handle)
+    }
+
+    /// The watcher gets a connection of its own. The notifications would otherwise be read off
+    /// the socket by whichever query of the context waits for a reply first.
+    pub(super) fn watch(&self) -> DIResult<Box<dyn DisplayEventSource>> {
+        Ok(Box::new(XorgEventSource::new(XorgContext::connect()?)?))
+    }
+}
+
+impl DisplayInfo {
+    /// Get all displays over an X server connection the application already holds, e.g. the
+    /// one of its windowing library, instead of opening a new one. `screen` is the screen index
+    /// returned with the connection.
+    ///
+    /// ```no_run
+    /// use display_info::DisplayInfo;
+    ///
+    /// let (conn, screen) = xcb::Connection::connect(None).unwrap();
+    /// let display_infos = DisplayInfo::all_with_xcb(&conn, screen).unwrap();
+    /// ```
+    pub fn all_with_xcb(conn: &Connection, screen: i32) -> DIResult<Vec<DisplayInfo>> {
+        let atoms = Atoms::intern_all(conn)?;
+
+        get_all(conn, get_screen(conn, screen)?, &atoms)
+    }
+}
+
+fn get_screen(conn: &Connection, index: i32) -> DIResult<&Screen> {
+    conn.get_setup()
+        .roots()
+        .nth(index as usize)
+        .ok_or_else(|| DIError::new("Not found screen"))
+}
+
+fn get_property(
+    conn: &Connection,
+    window: Window,
+    property: Atom,
+    r#type: Atom,
+    long_length: u32,
+) -> GetPropertyCookie {
+    conn.send_request(&GetProperty {
+        delete: false,
+        window,
+        property,
+        r#type,
+        long_offset: 0,
+        long_length,
+    })
+}
+
+fn get_edid(get_output_property_reply: &GetOutputPropertyReply) -> Option<Vec<u8>> {
+    // any client can set the property, with any format
+    if get_output_property_reply.format() != 8 {
+        return None;
+    }
+
+    let edid = get_output_property_reply.data::<u8>();
+
+    (!edid.is_empty()).then(|| edid.to_vec())
+}
+
+// per https://gitlab.freedesktop.org/xorg/app/xrandr/-/blob/master/xrandr.c#L576
+fn get_frequency(mode_info: &ModeInfo) -> f32 {
     let vtotal = {
         let mut val = mode_info.vtotal;
         if mode_info.mode_flags.contains(ModeFlag::DOUBLE_SCAN) {
@@ -46,21 +195,49 @@ fn get_current_frequency(mode_infos: &[ModeInfo], mode: Mode) -> f32 {
     }
 }
 
-fn get_scale_factor(conn: &Connection, screen: &Screen) -> DIResult<f32> {
-    let xft_dpi_prefix = "Xft.dpi:\t";
+fn get_current_frequency(mode_infos: &[ModeInfo], mode: Mode) -> f32 {
+    mode_infos
+        .iter()
+        .find(|m| m.id == mode.resource_id())
+        .map(get_frequency)
+        .unwrap_or(0.0)
+}
 
-    let get_property_cookie = conn.send_request(&GetProperty {
-        delete: false,
-        window: screen.root(),
-        property: ATOM_RESOURCE_MANAGER,
-        r#type: ATOM_STRING,
-        long_offset: 0,
-        long_length: 60,
-    });
+fn get_modes(
+    mode_infos: &[ModeInfo],
+    output_modes: &[Mode],
+    num_preferred: usize,
+    current_mode: Mode,
+) -> Vec<DisplayMode> {
+    output_modes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, mode)| {
+            let mode_info = mode_infos.iter().find(|m| m.id == mode.resource_id())?;
 
-    let get_property_reply = conn.wait_for_reply(get_property_cookie)?;
+            Some(DisplayMode {
+                id: mode_info.id,
+                width: mode_info.width as u32,
+                height: mode_info.height as u32,
+                refresh_rate: get_frequency(mode_info),
+                is_current: *mode == current_mode,
+                // the output's preferred modes come first in its mode list
+                is_preferred: index < num_preferred,
+                is_interlaced: mode_info.mode_flags.contains(ModeFlag::INTERLACE),
+                is_double_scan: mode_info.mode_flags.contains(ModeFlag::DOUBLE_SCAN),
+            })
+        })
+        .collect()
+}
 
-    let resource_manager = str::from_utf8(get_property_reply.value())?;
+fn get_scale_factor(resource_manager_reply: &GetPropertyReply) -> DIResult<f32> {
+    let xft_dpi_prefix = "Xft.dpi:\t";
+
+    if resource_manager_reply.format() != 8 {
+        return Err(DIError::new("RESOURCE_MANAGER is not a string"));
+    }
+
+    let resource_manager = str::from_utf8(resource_manager_reply.value())?;
 
     let xft_dpi = resource_manager
         .split('\n')
@@ -74,28 +251,226 @@ fn get_scale_factor(conn: &Connection, screen: &Screen) -> DIResult<f32> {
     Ok(dpi / 96.0)
 }
 
-fn get_rotation_frequency(
+/// `None` when the property is not set on the window, or not set as 32 bit values. Any client
+/// can set it with another format, which must not fail the queries.
+fn get_cardinals(get_property_reply: &GetPropertyReply) -> Option<Vec<u32>> {
+    if get_property_reply.r#type() == ATOM_NONE || get_property_reply.format() != 32 {
+        return None;
+    }
+
+    Some(get_property_reply.value::<u32>().to_vec())
+}
+
+/// Ask for the `_NET_WM_STRUT_PARTIAL` and `_NET_WM_STRUT` of every window.
+fn get_strut_cookies(
     conn: &Connection,
-    mode_infos: &[ModeInfo],
-    output: &Output,
-) -> DIResult<(f32, f32)> {
-    let get_output_info_cookie = conn.send_request(&GetOutputInfo {
-        output: *output,
-        config_timestamp: 0,
-    });
+    windows: &[u32],
+    atoms: &Atoms,
+) -> Vec<(GetPropertyCookie, GetPropertyCookie)> {
+    windows
+        .iter()
+        .map(|&window| {
+            let window = Window::new(window);
+            let strut_partial_cookie =
+                get_property(conn, window, atoms.net_wm_strut_partial, ATOM_CARDINAL, 12);
+            let strut_cookie = get_property(conn, window, atoms.net_wm_strut, ATOM_CARDINAL, 4);
 
-    let get_output_info_reply = conn.wait_for_reply(get_output_info_cookie)?;
+            (strut_partial_cookie, strut_cookie)
+        })
+        .collect()
+}
 
-    let get_crtc_info_cookie = conn.send_request(&GetCrtcInfo {
-        crtc: get_output_info_reply.crtc(),
-        config_timestamp: 0,
-    });
+/// Query the current size of the root window. The screen size in the connection setup is the
+/// one at connection time, which goes stale on a long-lived connection once the screen is
+/// resized.
+fn get_geometry(conn: &Connection, screen: &Screen) -> GetGeometryCookie {
+    conn.send_request(&GetGeometry {
+        drawable: Drawable::Window(screen.root()),
+    })
+}
 
-    let get_crtc_info_reply = conn.wait_for_reply(get_crtc_info_cookie)?;
+fn get_screen_size(get_geometry_reply: &GetGeometryReply) -> Size {
+    Size::new(
+        get_geometry_reply.width() as u32,
+        get_geometry_reply.height() as u32,
+    )
+}
+
+/// `_NET_WM_STRUT_PARTIAL` of every window, `_NET_WM_STRUT` of those that only have the
+/// older property.
+fn get_struts(
+    conn: &Connection,
+    screen_size: Size,
+    strut_cookies: Vec<(GetPropertyCookie, GetPropertyCookie)>,
+) -> Vec<[u32; 12]> {
+    let mut struts = Vec::new();
+    for (strut_partial_cookie, strut_cookie) in strut_cookies {
+        // windows may be destroyed in the meantime, skip them
+        let strut_partial = conn.wait_for_reply(strut_partial_cookie).ok();
+        let strut = conn.wait_for_reply(strut_cookie).ok();
+
+        struts.extend(get_strut(
+            strut_partial.as_ref(),
+            strut.as_ref(),
+            screen_size,
+        ));
+    }
+
+    struts
+}
+
+/// The strut of a window, `None` when neither property is a valid strut.
+fn get_strut(
+    strut_partial_reply: Option<&GetPropertyReply>,
+    strut_reply: Option<&GetPropertyReply>,
+    screen_size: Size,
+) -> Option<[u32; 12]> {
+    let strut_partial = strut_partial_reply
+        .and_then(get_cardinals)
+        .and_then(|values| <[u32; 12]>::try_from(values).ok());
+
+    strut_partial.or_else(|| {
+        strut_reply
+            .and_then(get_cardinals)
+            .and_then(|values| <[u32; 4]>::try_from(values).ok())
+            .map(|strut| strut_from_legacy(strut, screen_size))
+    })
+}
+
+/// Expand a `_NET_WM_STRUT` to a `_NET_WM_STRUT_PARTIAL`. A strut without spans covers its
+/// whole screen edge.
+fn strut_from_legacy([left, right, top, bottom]: [u32; 4], screen_size: Size) -> [u32; 12] {
+    let max_y = screen_size.height.saturating_sub(1);
+    let max_x = screen_size.width.saturating_sub(1);
+
+    [
+        left, right, top, bottom, 0, max_y, 0, max_y, 0, max_x, 0, max_x,
+    ]
+}
+
+/// `_NET_WORKAREA` of the current desktop, a single rectangle over all monitors.
+fn get_net_work_area(
+    work_area_reply: &GetPropertyReply,
+    current_desktop_reply: &GetPropertyReply,
+) -> Option<Rect> {
+    let work_areas = get_cardinals(work_area_reply)?;
+    let current_desktop = get_cardinals(current_desktop_reply)
+        .and_then(|desktops| desktops.first().copied())
+        .unwrap_or(0) as usize;
+
+    work_areas
+        .chunks_exact(4)
+        .nth(current_desktop)
+        .map(|area| Rect::new(area[0] as i32, area[1] as i32, area[2], area[3]))
+}
+
+/// The work area of a monitor from the struts of the windows, clamped by `_NET_WORKAREA`.
+///
+/// `_NET_WORKAREA` is a single rectangle over all monitors, so on its own it misses panels
+/// between monitors, but it also covers space the window manager reserves without a strut
+/// window, e.g. the top bar of mutter.
+fn get_work_area(
+    monitor: Rect,
+    screen_size: Size,
+    struts: &[[u32; 12]],
+    net_work_area: Option<Rect>,
+) -> Rect {
+    let work_area = get_strut_work_area(monitor, screen_size, struts);
+
+    net_work_area
+        .and_then(|net_work_area| work_area.intersection(&net_work_area))
+        .unwrap_or(work_area)
+}
+
+/// Struts are relative to the screen edges, so on multi-monitor setups a strut only applies to
+/// the monitor its inner edge falls on.
+fn get_strut_work_area(monitor: Rect, screen_size: Size, struts: &[[u32; 12]]) -> Rect {
+    let width = screen_size.width as i32;
+    let height = screen_size.height as i32;
 
+    let (mut left, mut top, mut right, mut bottom) =
+        (monitor.x, monitor.y, monitor.right(), monitor.bottom());
+
+    let overlaps = |start: i32, end: i32, min: i32, max: i32| start < max && end >= min;
+
+    for strut in struts {
+        let [
+            l,
+            r,
+            t,
+            b,
+            l_start,
+            l_end,
+            r_start,
+            r_end,
+            t_start,
+            t_end,
+            b_start,
+            b_end,
+        ] = strut.map(|v| v as i32);
+
+        if l > monitor.x
+            && l <= monitor.right()
+            && overlaps(l_start, l_end, monitor.y, monitor.bottom())
+        {
+            left = left.max(l);
+        }
+        if r > 0
+            && width - r >= monitor.x
+            && width - r < monitor.right()
+            && overlaps(r_start, r_end, monitor.y, monitor.bottom())
+        {
+            right = right.min(width - r);
+        }
+        if t > monitor.y
+            && t <= monitor.bottom()
+            && overlaps(t_start, t_end, monitor.x, monitor.right())
+        {
+            top = top.max(t);
+        }
+        if b > 0
+            && height - b >= monitor.y
+            && height - b < monitor.bottom()
+            && overlaps(b_start, b_end, monitor.x, monitor.right())
+        {
+            bottom = bottom.min(height - b);
+        }
+    }
+
+    Rect::from_edges(left, top, right, bottom)
+}
+
+/// A monitor of GetMonitors, without the borrow of the reply.
+#[derive(Debug)]
+struct Monitor {
+    /// In physical pixels.
+    bounds: Rect,
+    width_mm: i32,
+    height_mm: i32,
+    is_primary: bool,
+    name: Atom,
+    /// The first output of the monitor. `None` fails the enumeration once all replies are in.
+    output: Option<Output>,
+}
+
+#[derive(Debug, Default)]
+struct OutputDetails {
+    rotation: f32,
+    frequency: f32,
+    modes: Vec<DisplayMode>,
+}
+
+fn get_output_details(
+    mode_infos: &[ModeInfo],
+    get_output_info_reply: &GetOutputInfoReply,
+    get_crtc_info_reply: &GetCrtcInfoReply,
+) -> OutputDetails {
     let mode = get_crtc_info_reply.mode();
 
-    let rotation = match get_crtc_info_reply.rotation() {
+    // the rotation also carries the reflection bits
+    let rotation = match get_crtc_info_reply.rotation()
+        & (Rotation::ROTATE_0 | Rotation::ROTATE_90 | Rotation::ROTATE_180 | Rotation::ROTATE_270)
+    {
         Rotation::ROTATE_0 => 0.0,
         Rotation::ROTATE_90 => 90.0,
         Rotation::ROTATE_180 => 180.0,
@@ -105,83 +480,912 @@ fn get_rotation_frequency(
 
     let frequency = get_current_frequency(mode_infos, mode);
 
-    Ok((rotation, frequency))
-}
-
-pub fn get_all() -> DIResult<Vec<DisplayInfo>> {
-    let (conn, index) = Connection::connect(None)?;
-
-    let setup = conn.get_setup();
+    let modes = get_modes(
+        mode_infos,
+        get_output_info_reply.modes(),
+        get_output_info_reply.num_preferred() as usize,
+        mode,
+    );
 
-    let screen = setup
-        .roots()
-        .nth(index as usize)
-        .ok_or_else(|| DIError::new("Not found screen"))?;
+    OutputDetails {
+        rotation,
+        frequency,
+        modes,
+    }
+}
 
-    let scale_factor = get_scale_factor(&conn, screen).unwrap_or(1.0);
+/// The requests are sent in stages, each with the requests that only need the replies of the
+/// stages before, so the whole enumeration takes three round trips however many monitors there
+/// are. Every cookie of a stage is waited for before failing, a reply that is never read would
+/// stay queued on the connection.
+fn get_all(conn: &Connection, screen: &Screen, atoms: &Atoms) -> DIResult<Vec<DisplayInfo>> {
+    let root = screen.root();
 
+    // everything that doesn't depend on another reply
+    let resource_manager_cookie = get_property(conn, root, ATOM_RESOURCE_MANAGER, ATOM_STRING, 60);
     let get_monitors_cookie = conn.send_request(&GetMonitors {
-        window: screen.root(),
+        window: root,
         get_active: true,
     });
+    let get_screen_resources_cookie = conn.send_request(&GetScreenResources { window: root });
+    let get_output_primary_cookie = conn.send_request(&GetOutputPrimary { window: root });
+    let client_list_cookie = get_property(conn, root, atoms.net_client_list, ATOM_WINDOW, u32::MAX);
+    let work_area_cookie = get_property(conn, root, atoms.net_workarea, ATOM_CARDINAL, u32::MAX);
+    let current_desktop_cookie =
+        get_property(conn, root, atoms.net_current_desktop, ATOM_CARDINAL, 1);
+    let get_geometry_cookie = get_geometry(conn, screen);
 
-    let get_monitors_reply = conn.wait_for_reply(get_monitors_cookie)?;
+    let get_monitors_reply = conn.wait_for_reply(get_monitors_cookie);
+    let client_list_reply = conn.wait_for_reply(client_list_cookie);
 
-    let monitor_info_iterator = get_monitors_reply.monitors();
+    // windows may have struts only when the window manager keeps _NET_CLIENT_LIST
+    let strut_cookies = match &client_list_reply {
+        Ok(client_list_reply) => {
+            get_cardinals(client_list_reply).map(|windows| get_strut_cookies(conn, &windows, atoms))
+        }
+        Err(err) => {
+            log::info!("Get _NET_CLIENT_LIST failed: {}", err);
+            None
+        }
+    };
 
-    let get_screen_resources_cookie = conn.send_request(&GetScreenResources {
-        window: screen.root(),
+    // the names, outputs and EDIDs of all monitors
+    let monitors = get_monitors_reply.as_ref().map(|reply| {
+        reply
+            .monitors()
+            .map(|monitor_info| {
+                let monitor = Monitor {
+                    bounds: Rect::new(
+                        monitor_info.x() as i32,
+                        monitor_info.y() as i32,
+                        monitor_info.width() as u32,
+                        monitor_info.height() as u32,
+                    ),
+                    width_mm: monitor_info.width_in_millimeters() as i32,
+                    height_mm: monitor_info.height_in_millimeters() as i32,
+                    is_primary: monitor_info.primary(),
+                    name: monitor_info.name(),
+                    output: monitor_info.outputs().first().copied(),
+                };
+
+                let get_atom_name_cookie = conn.send_request(&GetAtomName { atom: monitor.name });
+                let output_cookies = monitor.output.map(|output| {
+                    let get_output_info_cookie = conn.send_request(&GetOutputInfo {
+                        output,
+                        config_timestamp: 0,
+                    });
+                    // 256 longs covers the base block and up to 7 extension blocks
+                    let get_output_property_cookie = conn.send_request(&GetOutputProperty {
+                        output,
+                        property: atoms.edid,
+                        r#type: ATOM_ANY,
+                        long_offset: 0,
+                        long_length: 256,
+                        delete: false,
+                        pending: false,
+                    });
+
+                    (get_output_info_cookie, get_output_property_cookie)
+                });
+
+                (monitor, get_atom_name_cookie, output_cookies)
+            })
+            .collect::<Vec<_>>()
     });
 
-    let get_screen_resources_reply = conn.wait_for_reply(get_screen_resources_cookie)?;
+    // the CRTCs of all outputs
+    let monitors = monitors
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(monitor, get_atom_name_cookie, output_cookies)| {
+            let output_replies =
+                output_cookies.map(|(get_output_info_cookie, get_output_property_cookie)| {
+                    let get_output_info_reply = conn.wait_for_reply(get_output_info_cookie);
+                    let get_crtc_info_cookie = get_output_info_reply
+                        .as_ref()
+                        .ok()
+                        .filter(|reply| !reply.crtc().is_none())
+                        .map(|reply| {
+                            conn.send_request(&GetCrtcInfo {
+                                crtc: reply.crtc(),
+                                config_timestamp: 0,
+                            })
+                        });
+
+                    (
+                        get_output_info_reply,
+                        get_output_property_cookie,
+                        get_crtc_info_cookie,
+                    )
+                });
+
+            (monitor, get_atom_name_cookie, output_replies)
+        })
+        .collect::<Vec<_>>();
+
+    // the remaining replies, all sent by now
+    let scale_factor = conn
+        .wait_for_reply(resource_manager_cookie)
+        .map_err(DIError::from)
+        .and_then(|reply| get_scale_factor(&reply))
+        .unwrap_or(1.0);
+    let get_screen_resources_reply = conn.wait_for_reply(get_screen_resources_cookie);
+    // GetMonitors doesn't always flag a primary monitor, e.g. when it was set with
+    // SetOutputPrimary after the monitors were created
+    let primary_output = conn
+        .wait_for_reply(get_output_primary_cookie)
+        .ok()
+        .map(|reply| reply.output())
+        .filter(|output| !output.is_none());
+    let work_area_reply = conn.wait_for_reply(work_area_cookie);
+    let current_desktop_reply = conn.wait_for_reply(current_desktop_cookie);
+    let screen_size = conn
+        .wait_for_reply(get_geometry_cookie)
+        .map(|reply| get_screen_size(&reply))
+        .unwrap_or_else(|err| {
+            log::info!("Get root window geometry failed: {}", err);
+            Size::new(
+                screen.width_in_pixels() as u32,
+                screen.height_in_pixels() as u32,
+            )
+        });
+    let struts = strut_cookies.map(|strut_cookies| get_struts(conn, screen_size, strut_cookies));
+
+    let monitors = monitors
+        .into_iter()
+        .map(|(monitor, get_atom_name_cookie, output_replies)| {
+            let name = conn
+                .wait_for_reply(get_atom_name_cookie)
+                .map(|reply| reply.name().to_string());
+
+            let output_replies = output_replies.map(
+                |(get_output_info_reply, get_output_property_cookie, get_crtc_info_cookie)| {
+                    let edid = conn.wait_for_reply(get_output_property_cookie);
+                    let get_crtc_info_reply = get_crtc_info_cookie
+                        .map(|get_crtc_info_cookie| conn.wait_for_reply(get_crtc_info_cookie));
+
+                    (get_output_info_reply, edid, get_crtc_info_reply)
+                },
+            );
+
+            (monitor, name, output_replies)
+        })
+        .collect::<Vec<_>>();
+
+    get_monitors_reply?;
+    let get_screen_resources_reply = get_screen_resources_reply?;
 
     let mode_infos = get_screen_resources_reply.modes();
 
+    let net_work_area = match (work_area_reply, current_desktop_reply) {
+        (Ok(work_area_reply), Ok(current_desktop_reply)) => {
+            get_net_work_area(&work_area_reply, &current_desktop_reply)
+        }
+        (Err(err), _) | (_, Err(err)) => {
+            log::info!("Get _NET_WORKAREA failed: {}", err);
+            None
+        }
+    };
+
     let mut display_infos = Vec::new();
 
-    for monitor_info in monitor_info_iterator {
-        let output = monitor_info
-            .outputs()
-            .first()
+    for (monitor, name, output_replies) in monitors {
+        // the replies of every monitor are waited above, so none are left unread
+        let (output, (get_output_info_reply, edid, get_crtc_info_reply)) = monitor
+            .output
+            .zip(output_replies)
             .ok_or_else(|| DIError::new("Not found output"))?;
+        let name = name?;
 
-        let (rotation, frequency) =
-            get_rotation_frequency(&conn, mode_infos, output).unwrap_or((0.0, 0.0));
+        let output_details = match (get_output_info_reply, get_crtc_info_reply) {
+            (Ok(get_output_info_reply), Some(Ok(get_crtc_info_reply))) => {
+                get_output_details(mode_infos, &get_output_info_reply, &get_crtc_info_reply)
+            }
+            _ => OutputDetails::default(),
+        };
+        let edid = edid.map(|reply| get_edid(&reply)).unwrap_or_else(|err| {
+            log::info!("Get EDID of output {:?} failed: {}", output, err);
+            None
+        });
+        let parsed_edid = edid::parse_raw(edid.as_deref());
 
-        let name = get_name(&conn, monitor_info.name())?;
+        let bounds = monitor.bounds;
+        let work_area = get_work_area(
+            bounds,
+            screen_size,
+            struts.as_deref().unwrap_or_default(),
+            net_work_area,
+        );
 
         display_infos.push(DisplayInfo {
             id: output.resource_id(),
             name: name.clone(),
-            friendly_name: name,
-            raw_handle: *output,
-            x: ((monitor_info.x() as f32) / scale_factor) as i32,
-            y: ((monitor_info.y() as f32) / scale_factor) as i32,
-            width: ((monitor_info.width() as f32) / scale_factor) as u32,
-            height: ((monitor_info.height() as f32) / scale_factor) as u32,
-            width_mm: monitor_info.width_in_millimeters() as i32,
-            height_mm: monitor_info.height_in_millimeters() as i32,
-            rotation,
+            friendly_name: name.clone(),
+            raw_handle: output,
+            x: ((bounds.x as f32) / scale_factor) as i32,
+            y: ((bounds.y as f32) / scale_factor) as i32,
+            width: ((bounds.width as f32) / scale_factor) as u32,
+            height: ((bounds.height as f32) / scale_factor) as u32,
+            width_mm: monitor.width_mm,
+            height_mm: monitor.height_mm,
+            rotation: output_details.rotation,
             scale_factor,
-            frequency,
-            is_primary: monitor_info.primary(),
+            frequency: output_details.frequency,
+            is_primary: monitor.is_primary || primary_output == Some(output),
+            edid,
+            hdr: parsed_edid.as_ref().and_then(HdrInfo::from_edid),
+            stable_id: stable_id(parsed_edid.as_ref(), &name),
+            modes: output_details.modes,
+            work_area: Rect::new(
+                ((work_area.x as f32) / scale_factor) as i32,
+                ((work_area.y as f32) / scale_factor) as i32,
+                ((work_area.width as f32) / scale_factor) as u32,
+                ((work_area.height as f32) / scale_factor) as u32,
+            ),
+            work_area_size: None,
         });
     }
 
     Ok(display_infos)
 }
 
-pub fn get_from_point(x: i32, y: i32) -> DIResult<DisplayInfo> {
-    let display_infos = DisplayInfo::all()?;
-
-    display_infos
+/// Grow the screen when the CRTCs, with `crtc` extending to `extent`, don't fit in it anymore.
+fn grow_screen(
+    conn: &Connection,
+    screen: &Screen,
+    crtcs: &[Crtc],
+    crtc: Crtc,
+    extent: (i32, i32),
+    config_timestamp: Timestamp,
+) -> DIResult<()> {
+    let get_geometry_cookie = get_geometry(conn, screen);
+    let get_crtc_info_cookies = crtcs
         .iter()
-        .find(|&display_info| {
-            x >= display_info.x
-                && x < display_info.x + display_info.width as i32
-                && y >= display_info.y
-                && y < display_info.y + display_info.height as i32
+        .filter(|&&c| c != crtc)
+        .map(|&crtc| {
+            conn.send_request(&GetCrtcInfo {
+                crtc,
+                config_timestamp,
+            })
         })
-        .cloned()
-        .ok_or_else(|| DIError::new("Get display info failed"))
+        .collect::<Vec<_>>();
+
+    let get_geometry_reply = conn.wait_for_reply(get_geometry_cookie);
+    let get_crtc_info_replies = get_crtc_info_cookies
+        .into_iter()
+        .map(|get_crtc_info_cookie| conn.wait_for_reply(get_crtc_info_cookie))
+        .collect::<Vec<_>>();
+    let screen_size = get_screen_size(&get_geometry_reply?);
+
+    let (mut width, mut height) = extent;
+    for get_crtc_info_reply in get_crtc_info_replies {
+        let get_crtc_info_reply = get_crtc_info_reply?;
+        if get_crtc_info_reply.mode().is_none() {
+            continue;
+        }
+
+        width = width.max(get_crtc_info_reply.x() as i32 + get_crtc_info_reply.width() as i32);
+        height = height.max(get_crtc_info_reply.y() as i32 + get_crtc_info_reply.height() as i32);
+    }
+
+    let screen_width = screen_size.width as i32;
+    let screen_height = screen_size.height as i32;
+    if width <= screen_width && height <= screen_height {
+        return Ok(());
+    }
+
+    let width = width.max(screen_width);
+    let height = height.max(screen_height);
+
+    let get_screen_size_range_cookie = conn.send_request(&GetScreenSizeRange {
+        window: screen.root(),
+    });
+    let get_screen_size_range_reply = conn.wait_for_reply(get_screen_size_range_cookie)?;
+
+    let max_width = get_screen_size_range_reply.max_width() as i32;
+    let max_height = get_screen_size_range_reply.max_height() as i32;
+    if width > max_width || height > max_height {
+        return Err(DIError::new(format!(
+            "Screen size {}x{} exceeds the maximum {}x{}",
+            width, height, max_width, max_height
+        )));
+    }
+
+    // keep the screen DPI, which only the connection setup reports
+    let mm_width = width as f32 * screen.width_in_millimeters() as f32
+        / screen.width_in_pixels().max(1) as f32;
+    let mm_height = height as f32 * screen.height_in_millimeters() as f32
+        / screen.height_in_pixels().max(1) as f32;
+
+    let set_screen_size_cookie = conn.send_request_checked(&SetScreenSize {
+        window: screen.root(),
+        width: width as u16,
+        height: height as u16,
+        mm_width: mm_width as u32,
+        mm_height: mm_height as u32,
+    });
+    conn.check_request(set_screen_size_cookie)?;
+
+    Ok(())
+}
+
+/// Reconfigure the CRTC driving `output`, keeping its current mode or rotation when `None`.
+fn set_crtc_config(
+    conn: &Connection,
+    screen: &Screen,
+    output: Output,
+    mode: Option<u32>,
+    rotation: Option<Rotation>,
+) -> DIResult<()> {
+    let get_screen_resources_cookie = conn.send_request(&GetScreenResources {
+        window: screen.root(),
+    });
+    let get_screen_resources_reply = conn.wait_for_reply(get_screen_resources_cookie)?;
+    let config_timestamp = get_screen_resources_reply.config_timestamp();
+
+    let get_output_info_cookie = conn.send_request(&GetOutputInfo {
+        output,
+        config_timestamp,
+    });
+    let get_output_info_reply = conn.wait_for_reply(get_output_info_cookie)?;
+
+    if let Some(mode) = mode
+        && !get_output_info_reply
+            .modes()
+            .iter()
+            .any(|m| m.resource_id() == mode)
+    {
+        return Err(DIError::InvalidMode(mode));
+    }
+
+    let crtc = get_output_info_reply.crtc();
+    if crtc.is_none() {
+        return Err(DIError::new("Output is not connected to a CRTC"));
+    }
+
+    let get_crtc_info_cookie = conn.send_request(&GetCrtcInfo {
+        crtc,
+        config_timestamp,
+    });
+    let get_crtc_info_reply = conn.wait_for_reply(get_crtc_info_cookie)?;
+
+    let mode = mode
+        .map(Mode::new)
+        .unwrap_or_else(|| get_crtc_info_reply.mode());
+    let rotation = rotation.unwrap_or_else(|| get_crtc_info_reply.rotation());
+
+    if !get_crtc_info_reply.rotations().contains(rotation) {
+        return Err(DIError::UnsupportedRotation(rotation));
+    }
+
+    let mode_info = get_screen_resources_reply
+        .modes()
+        .iter()
+        .find(|m| m.id == mode.resource_id())
+        .ok_or(DIError::InvalidMode(mode.resource_id()))?;
+
+    let (width, height) = if rotation.intersects(Rotation::ROTATE_90 | Rotation::ROTATE_270) {
+        (mode_info.height, mode_info.width)
+    } else {
+        (mode_info.width, mode_info.height)
+    };
+
+    grow_screen(
+        conn,
+        screen,
+        get_screen_resources_reply.crtcs(),
+        crtc,
+        (
+            get_crtc_info_reply.x() as i32 + width as i32,
+            get_crtc_info_reply.y() as i32 + height as i32,
+        ),
+        config_timestamp,
+    )?;
+
+    let set_crtc_config_cookie = conn.send_request(&SetCrtcConfig {
+        crtc,
+        timestamp: get_crtc_info_reply.timestamp(),
+        config_timestamp,
+        x: get_crtc_info_reply.x(),
+        y: get_crtc_info_reply.y(),
+        mode,
+        rotation,
+        outputs: get_crtc_info_reply.outputs(),
+    });
+    let set_crtc_config_reply = conn.wait_for_reply(set_crtc_config_cookie)?;
+
+    match set_crtc_config_reply.status() {
+        SetConfig::Success => Ok(()),
+        status => Err(DIError::RandrConfigFailed(status)),
+    }
+}
+
+fn get_randr_rotation(rotation: f32, reflection: Reflection) -> DIResult<Rotation> {
+    let mut randr_rotation = match rotation {
+        0.0 => Rotation::ROTATE_0,
+        90.0 => Rotation::ROTATE_90,
+        180.0 => Rotation::ROTATE_180,
+        270.0 => Rotation::ROTATE_270,
+        _ => return Err(DIError::InvalidRotation(rotation)),
+    };
+
+    if reflection.x {
+        randr_rotation |= Rotation::REFLECT_X;
+    }
+    if reflection.y {
+        randr_rotation |= Rotation::REFLECT_Y;
+    }
+
+    Ok(randr_rotation)
+}
+
+fn set_primary(conn: &Connection, screen: &Screen, output: Output) -> DIResult<()> {
+    let set_output_primary_cookie = conn.send_request_checked(&SetOutputPrimary {
+        window: screen.root(),
+        output,
+    });
+    conn.check_request(set_output_primary_cookie)?;
+
+    Ok(())
+}
+
+/// RandR screen, CRTC and output change notifications, on a connection owned by the watcher.
+struct XorgEventSource {
+    context: XorgContext,
+    displays: Vec<DisplayInfo>,
+}
+
+impl XorgEventSource {
+    fn new(context: XorgContext) -> DIResult<Self> {
+        select_input(&context)?;
+        let displays = context.get_all()?;
+
+        Ok(XorgEventSource { context, displays })
+    }
+
+    fn get_events(&mut self) -> DIResult<Vec<DisplayEvent>> {
+        // the notifications only name the changed resources, compare full snapshots
+        let displays = self.context.get_all()?;
+        let events = watcher::get_events(&DisplayDiff::new(&self.displays, &displays));
+        self.displays = displays;
+
+        Ok(events)
+    }
+}
+
+/// The selection ends with the connection of the watcher.
+fn select_input(context: &XorgContext) -> DIResult<()> {
+    let select_input_cookie = context.conn.send_request_checked(&SelectInput {
+        window: context.screen()?.root(),
+        enable: NotifyMask::SCREEN_CHANGE | NotifyMask::CRTC_CHANGE | NotifyMask::OUTPUT_CHANGE,
+    });
+    context.conn.check_request(select_input_cookie)?;
+
+    Ok(())
+}
+
+impl DisplayEventSource for XorgEventSource {
+    fn next_events(&mut self) -> DIResult<Vec<DisplayEvent>> {
+        loop {
+            if let Event::RandR(_) = self.context.conn.wait_for_event()? {
+                // a single reconfiguration sends a burst of notifications, handle them at once
+                while self.context.conn.poll_for_queued_event()?.is_some() {}
+
+                let events = self.get_events()?;
+                if !events.is_empty() {
+                    return Ok(events);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn as_raw_fd(&self) -> Option<std::os::fd::RawFd> {
+        use std::os::fd::AsRawFd;
+
+        Some(self.context.conn.as_raw_fd())
+    }
+
+    #[cfg(feature = "async")]
+    fn try_next_events(&mut self) -> DIResult<Vec<DisplayEvent>> {
+        let mut has_changes = false;
+        while let Some(event) = self.context.conn.poll_for_event()? {
+            has_changes |= matches!(event, Event::RandR(_));
+        }
+
+        if !has_changes {
+            return Ok(Vec::new());
+        }
+
+        self.get_events()
+    }
+}
+
+impl DisplayBackend for XorgBackend {
+    fn name(&self) -> &str {
+        "xorg"
+    }
+
+    fn get_all(&self) -> DIResult<Vec<DisplayInfo>> {
+        XorgContext::connect()?.get_all()
+    }
+
+    fn set_mode(&self, display: &DisplayInfo, mode: &DisplayMode) -> DIResult<()> {
+        XorgContext::connect()?.set_mode(display, mode)
+    }
+
+    fn set_rotation(
+        &self,
+        display: &DisplayInfo,
+        rotation: f32,
+        reflection: Reflection,
+    ) -> DIResult<()> {
+        XorgContext::connect()?.set_rotation(display, rotation, reflection)
+    }
+
+    fn set_primary(&self, display: &DisplayInfo) -> DIResult<()> {
+        XorgContext::connect()?.set_primary(display)
+    }
+
+    fn watch(&self) -> DIResult<Box<dyn DisplayEventSource>> {
+        Ok(Box::new(XorgEventSource::new(XorgContext::connect()?)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `_NET_WM_STRUT_PARTIAL` reserving `width` pixels from one screen edge over `start..=end`.
+    fn strut(edge: usize, width: u32, start: u32, end: u32) -> [u32; 12] {
+        let mut strut = [0; 12];
+        strut[edge] = width;
+        strut[4 + edge * 2] = start;
+        strut[5 + edge * 2] = end;
+        strut
+    }
+
+    const LEFT: usize = 0;
+    const RIGHT: usize = 1;
+    const TOP: usize = 2;
+    const BOTTOM: usize = 3;
+
+    /// A property reply as the X server sends it, `value` holding items of `format` bits.
+    /// GetProperty and GetOutputProperty replies share this layout.
+    fn property_reply<R: xcb::Reply>(format: u8, value: &[u8]) -> R {
+        let mut wire = vec![0u8; 32];
+        wire[0] = 1;
+        wire[1] = format;
+        wire[4..8].copy_from_slice(&(value.len().div_ceil(4) as u32).to_ne_bytes());
+        wire[8..12].copy_from_slice(&ATOM_CARDINAL.resource_id().to_ne_bytes());
+        let items = value.len() / (format as usize / 8);
+        wire[16..20].copy_from_slice(&(items as u32).to_ne_bytes());
+        wire.extend_from_slice(value);
+
+        unsafe {
+            let raw = libc::malloc(wire.len()) as *mut u8;
+            raw.copy_from_nonoverlapping(wire.as_ptr(), wire.len());
+            R::from_raw(raw)
+        }
+    }
+
+    fn cardinals<const N: usize>(values: [u32; N]) -> GetPropertyReply {
+        property_reply(32, &values.map(u32::to_ne_bytes).concat())
+    }
+
+    fn shorts<const N: usize>(values: [u16; N]) -> GetPropertyReply {
+        property_reply(16, &values.map(u16::to_ne_bytes).concat())
+    }
+
+    #[test]
+    fn randr_rotation() {
+        let none = Reflection::default();
+
+        assert_eq!(get_randr_rotation(0.0, none).unwrap(), Rotation::ROTATE_0);
+        assert_eq!(get_randr_rotation(-0.0, none).unwrap(), Rotation::ROTATE_0);
+        assert_eq!(get_randr_rotation(90.0, none).unwrap(), Rotation::ROTATE_90);
+        assert_eq!(
+            get_randr_rotation(180.0, none).unwrap(),
+            Rotation::ROTATE_180
+        );
+        assert_eq!(
+            get_randr_rotation(270.0, Reflection { x: true, y: true }).unwrap(),
+            Rotation::ROTATE_270 | Rotation::REFLECT_X | Rotation::REFLECT_Y
+        );
+        assert_eq!(
+            get_randr_rotation(0.0, Reflection { x: false, y: true }).unwrap(),
+            Rotation::ROTATE_0 | Rotation::REFLECT_Y
+        );
+    }
+
+    #[test]
+    fn randr_rotation_invalid() {
+        for rotation in [90.7, 89.9, 45.0, 360.0, -90.0, f32::NAN, f32::INFINITY] {
+            assert!(
+                matches!(
+                    get_randr_rotation(rotation, Reflection::default()),
+                    Err(DIError::InvalidRotation(r)) if r.to_bits() == rotation.to_bits()
+                ),
+                "{rotation}"
+            );
+        }
+    }
+
+    #[test]
+    fn cardinals_of_other_formats() {
+        assert_eq!(get_cardinals(&cardinals([1, 2])), Some(vec![1, 2]));
+        assert_eq!(get_cardinals(&shorts([1, 2])), None);
+        assert_eq!(get_cardinals(&property_reply(8, b"12")), None);
+    }
+
+    #[test]
+    fn strut_of_other_formats() {
+        let screen_size = Size::new(1920, 1080);
+        let strut_partial = strut(TOP, 30, 0, 1919);
+        let legacy = [0, 0, 30, 0];
+        let expanded = strut_from_legacy(legacy, screen_size);
+
+        assert_eq!(
+            get_strut(Some(&cardinals(strut_partial)), None, screen_size),
+            Some(strut_partial)
+        );
+        assert_eq!(
+            get_strut(None, Some(&cardinals(legacy)), screen_size),
+            Some(expanded)
+        );
+        assert_eq!(
+            get_strut(Some(&shorts([0, 0, 30, 0])), None, screen_size),
+            None
+        );
+        assert_eq!(
+            get_strut(
+                Some(&shorts(strut_partial.map(|v| v as u16))),
+                Some(&cardinals(legacy)),
+                screen_size
+            ),
+            Some(expanded)
+        );
+        assert_eq!(
+            get_strut(Some(&cardinals([30, 0])), None, screen_size),
+            None
+        );
+    }
+
+    #[test]
+    fn edid_of_other_formats() {
+        let edid = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+
+        assert_eq!(
+            get_edid(&property_reply::<GetOutputPropertyReply>(8, &edid)),
+            Some(edid.to_vec())
+        );
+        assert_eq!(
+            get_edid(&property_reply::<GetOutputPropertyReply>(16, &edid)),
+            None
+        );
+        assert_eq!(
+            get_edid(&property_reply::<GetOutputPropertyReply>(32, &edid)),
+            None
+        );
+    }
+
+    #[test]
+    fn work_area_with_net_work_area() {
+        let screen_size = Size::new(3840, 1080);
+        let first = Rect::new(0, 0, 1920, 1080);
+        let second = Rect::new(1920, 0, 1920, 1080);
+        // a top bar of the window manager, without a strut window
+        let top_bar = Some(Rect::new(0, 32, 3840, 1048));
+
+        let cases = [
+            ("nothing reserved", vec![], None, first, first),
+            (
+                "top bar without struts, first",
+                vec![],
+                top_bar,
+                first,
+                Rect::new(0, 32, 1920, 1048),
+            ),
+            (
+                "top bar without struts, second",
+                vec![],
+                top_bar,
+                second,
+                Rect::new(1920, 32, 1920, 1048),
+            ),
+            (
+                "top bar and a bottom panel on the second monitor, first",
+                vec![strut(BOTTOM, 40, 1920, 3839)],
+                top_bar,
+                first,
+                Rect::new(0, 32, 1920, 1048),
+            ),
+            (
+                "top bar and a bottom panel on the second monitor, second",
+                vec![strut(BOTTOM, 40, 1920, 3839)],
+                top_bar,
+                second,
+                Rect::new(1920, 32, 1920, 1008),
+            ),
+            (
+                "struts without _NET_WORKAREA",
+                vec![strut(LEFT, 40, 0, 1079)],
+                None,
+                first,
+                Rect::new(40, 0, 1880, 1080),
+            ),
+            (
+                "_NET_WORKAREA away from the monitor",
+                vec![strut(LEFT, 40, 0, 1079)],
+                Some(Rect::new(1920, 0, 1920, 1080)),
+                first,
+                Rect::new(40, 0, 1880, 1080),
+            ),
+        ];
+
+        for (name, struts, net_work_area, monitor, expected) in cases {
+            assert_eq!(
+                get_work_area(monitor, screen_size, &struts, net_work_area),
+                expected,
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn work_area_side_by_side() {
+        let screen_size = Size::new(3840, 1080);
+        let first = Rect::new(0, 0, 1920, 1080);
+        let second = Rect::new(1920, 0, 1920, 1080);
+
+        let cases = [
+            ("no struts", vec![], first, first),
+            (
+                "bottom panel over both monitors, first",
+                vec![strut(BOTTOM, 40, 0, 3839)],
+                first,
+                Rect::new(0, 0, 1920, 1040),
+            ),
+            (
+                "bottom panel over both monitors, second",
+                vec![strut(BOTTOM, 40, 0, 3839)],
+                second,
+                Rect::new(1920, 0, 1920, 1040),
+            ),
+            (
+                "bottom panel on the second monitor, first",
+                vec![strut(BOTTOM, 40, 1920, 3839)],
+                first,
+                first,
+            ),
+            (
+                "left panel on the second monitor, first",
+                vec![strut(LEFT, 1960, 0, 1079)],
+                first,
+                first,
+            ),
+            (
+                "left panel on the second monitor, second",
+                vec![strut(LEFT, 1960, 0, 1079)],
+                second,
+                Rect::new(1960, 0, 1880, 1080),
+            ),
+            (
+                "right panel on the first monitor, first",
+                vec![strut(RIGHT, 1960, 0, 1079)],
+                first,
+                Rect::new(0, 0, 1880, 1080),
+            ),
+            (
+                "right panel on the first monitor, second",
+                vec![strut(RIGHT, 1960, 0, 1079)],
+                second,
+                second,
+            ),
+            (
+                "right panel on the second monitor, second",
+                vec![strut(RIGHT, 40, 0, 1079)],
+                second,
+                Rect::new(1920, 0, 1880, 1080),
+            ),
+            (
+                "top panel spanning only the first monitor, second",
+                vec![strut(TOP, 30, 0, 1919)],
+                second,
+                second,
+            ),
+            (
+                "top and left panels on the second monitor",
+                vec![strut(TOP, 30, 1920, 3839), strut(LEFT, 1980, 30, 1079)],
+                second,
+                Rect::new(1980, 30, 1860, 1050),
+            ),
+        ];
+
+        for (name, struts, monitor, expected) in cases {
+            assert_eq!(
+                get_strut_work_area(monitor, screen_size, &struts),
+                expected,
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn work_area_stacked() {
+        let screen_size = Size::new(1920, 2160);
+        let top = Rect::new(0, 0, 1920, 1080);
+        let bottom = Rect::new(0, 1080, 1920, 1080);
+
+        let cases = [
+            (
+                "left panel on the bottom monitor, top",
+                vec![strut(LEFT, 40, 1080, 2159)],
+                top,
+                top,
+            ),
+            (
+                "left panel on the bottom monitor, bottom",
+                vec![strut(LEFT, 40, 1080, 2159)],
+                bottom,
+                Rect::new(40, 1080, 1880, 1080),
+            ),
+            (
+                "bottom panel, top",
+                vec![strut(BOTTOM, 40, 0, 1919)],
+                top,
+                top,
+            ),
+            (
+                "top panel on the bottom monitor, bottom",
+                vec![strut(TOP, 1110, 0, 1919)],
+                bottom,
+                Rect::new(0, 1110, 1920, 1050),
+            ),
+        ];
+
+        for (name, struts, monitor, expected) in cases {
+            assert_eq!(
+                get_strut_work_area(monitor, screen_size, &struts),
+                expected,
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn work_area_different_heights() {
+        // the second monitor is shorter, so the bottom screen edge is only on the first
+        let screen_size = Size::new(3840, 1080);
+        let struts = [strut(BOTTOM, 40, 0, 3839)];
+
+        assert_eq!(
+            get_strut_work_area(Rect::new(0, 0, 1920, 1080), screen_size, &struts),
+            Rect::new(0, 0, 1920, 1040)
+        );
+        assert_eq!(
+            get_strut_work_area(Rect::new(1920, 0, 1920, 720), screen_size, &struts),
+            Rect::new(1920, 0, 1920, 720)
+        );
+    }
+
+    #[test]
+    fn work_area_legacy_strut() {
+        let screen_size = Size::new(3840, 1080);
+        let struts = [strut_from_legacy([0, 0, 30, 0], screen_size)];
+
+        assert_eq!(struts[0], [0, 0, 30, 0, 0, 1079, 0, 1079, 0, 3839, 0, 3839]);
+        assert_eq!(
+            get_strut_work_area(Rect::new(0, 0, 1920, 1080), screen_size, &struts),
+            Rect::new(0, 30, 1920, 1050)
+        );
+        assert_eq!(
+            get_strut_work_area(Rect::new(1920, 0, 1920, 1080), screen_size, &struts),
+            Rect::new(1920, 30, 1920, 1050)
+        );
+        assert_eq!(
+            strut_from_legacy([1, 2, 3, 4], Size::default()),
+            [1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+    }
 }
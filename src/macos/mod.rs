@@ -1,20 +1,26 @@
 use objc2::MainThreadMarker;
 use objc2_app_kit::NSScreen;
-use objc2_core_foundation::{CGPoint, CGRect};
+use objc2_core_foundation::CGRect;
 use objc2_core_graphics::{
     CGDirectDisplayID, CGDisplayBounds, CGDisplayCopyDisplayMode, CGDisplayIsMain, CGDisplayMode,
     CGDisplayRotation, CGDisplayScreenSize, CGError, CGGetActiveDisplayList,
-    CGGetDisplaysWithPoint,
 };
 use objc2_foundation::{NSNumber, NSString};
 
 use crate::{
     DisplayInfo,
+    backend::DisplayBackend,
     error::{DIError, DIResult},
+    geometry::Rect,
+    stable_id,
 };
 
 pub type ScreenRawHandle = CGDirectDisplayID;
 
+/// Display information from Core Graphics.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MacosBackend;
+
 fn get_display_friendly_name(display_id: CGDirectDisplayID) -> DIResult<String> {
     let screens = NSScreen::screens(unsafe { MainThreadMarker::new_unchecked() });
     for screen in screens {
@@ -70,11 +76,28 @@ impl DisplayInfo {
                 frequency,
                 scale_factor,
                 is_primary,
+                edid: None,
+                hdr: None,
+                stable_id: stable_id(None, &format!("Display {id}")),
+                modes: Vec::new(),
+                work_area: Rect::new(
+                    origin.x as i32,
+                    origin.y as i32,
+                    size.width as u32,
+                    size.height as u32,
+                ),
+                work_area_size: None,
             })
         }
     }
+}
+
+impl DisplayBackend for MacosBackend {
+    fn name(&self) -> &str {
+        "macos"
+    }
 
-    pub fn all() -> DIResult<Vec<DisplayInfo>> {
+    fn get_all(&self) -> DIResult<Vec<DisplayInfo>> {
         let max_displays: u32 = 16;
         let mut active_displays: Vec<CGDirectDisplayID> = vec![0; max_displays as usize];
         let mut display_count: u32 = 0;
@@ -104,37 +127,4 @@ impl DisplayInfo {
 
         Ok(display_infos)
     }
-
-    pub fn from_point(x: i32, y: i32) -> DIResult<DisplayInfo> {
-        let point = CGPoint {
-            x: x as f64,
-            y: y as f64,
-        };
-
-        let max_displays: u32 = 16;
-        let mut display_ids: Vec<CGDirectDisplayID> = vec![0; max_displays as usize];
-        let mut display_count: u32 = 0;
-
-        let cg_error = unsafe {
-            CGGetDisplaysWithPoint(
-                point,
-                max_displays,
-                display_ids.as_mut_ptr(),
-                &mut display_count,
-            )
-        };
-
-        if cg_error != CGError::Success {
-            return Err(DIError::new(format!(
-                "CGGetDisplaysWithPoint failed: {:?}",
-                cg_error
-            )));
-        }
-
-        if let Some(&display_id) = display_ids.first() {
-            DisplayInfo::new(display_id)
-        } else {
-            Err(DIError::new("Display not found"))
-        }
-    }
 }
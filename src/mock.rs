@@ -0,0 +1,200 @@
+//! A backend that answers display queries from a fixture file instead of a display server.
+//!
+//! Fixtures are JSON or TOML documents with a `displays` list:
+//!
+//! ```toml
+//! [[displays]]
+//! id = 1
+//! name = "DP-1"
+//! width = 2560
+//! height = 1440
+//! scale_factor = 2.0
+//! is_primary = true
+//!
+//! [[displays]]
+//! id = 2
+//! name = "HDMI-1"
+//! x = -1080
+//! y = -200
+//! width = 1080
+//! height = 1920
+//! rotation = 90.0
+//! ```
+//!
+//! Register it with [`set_backend`](crate::backend::set_backend), or with
+//! [`set_thread_backend`](crate::backend::set_thread_backend) in tests that run in parallel.
+//! Point the `DISPLAY_INFO_MOCK_FIXTURE` environment variable at a fixture file to have it
+//! picked up in place of the platform backend; the file is read on the first query.
+
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    DisplayInfo, DisplayMode, HdrInfo,
+    backend::DisplayBackend,
+    edid,
+    error::{DIError, DIResult},
+    geometry::{Rect, Size},
+    raw_handle, stable_id,
+};
+
+/// Environment variable naming a fixture file to use instead of the platform backend.
+pub const MOCK_FIXTURE_ENV: &str = "DISPLAY_INFO_MOCK_FIXTURE";
+
+/// One display of a fixture. Omitted fields take their default value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MockDisplay {
+    pub id: u32,
+    pub name: String,
+    /// Falls back to `name` when empty.
+    pub friendly_name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub width_mm: i32,
+    pub height_mm: i32,
+    pub rotation: f32,
+    pub scale_factor: f32,
+    pub frequency: f32,
+    pub is_primary: bool,
+    pub edid: Option<Vec<u8>>,
+    /// Derived from `edid` when unset.
+    pub hdr: Option<HdrInfo>,
+    /// Derived from `edid` and `name` when empty.
+    pub stable_id: String,
+    pub modes: Vec<DisplayMode>,
+    /// The whole display when unset.
+    pub work_area: Option<Rect>,
+    pub work_area_size: Option<Size>,
+}
+
+impl Default for MockDisplay {
+    fn default() -> Self {
+        MockDisplay {
+            id: 0,
+            name: String::new(),
+            friendly_name: String::new(),
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+            width_mm: 0,
+            height_mm: 0,
+            rotation: 0.0,
+            scale_factor: 1.0,
+            frequency: 60.0,
+            is_primary: false,
+            edid: None,
+            hdr: None,
+            stable_id: String::new(),
+            modes: Vec::new(),
+            work_area: None,
+            work_area_size: None,
+        }
+    }
+}
+
+impl From<&MockDisplay> for DisplayInfo {
+    fn from(display: &MockDisplay) -> Self {
+        let friendly_name = if display.friendly_name.is_empty() {
+            display.name.clone()
+        } else {
+            display.friendly_name.clone()
+        };
+        let parsed_edid = edid::parse_raw(display.edid.as_deref());
+        let stable_id = if display.stable_id.is_empty() {
+            stable_id(parsed_edid.as_ref(), &display.name)
+        } else {
+            display.stable_id.clone()
+        };
+
+        DisplayInfo {
+            id: display.id,
+            name: display.name.clone(),
+            friendly_name,
+            raw_handle: raw_handle::from_u64(display.id as u64),
+            x: display.x,
+            y: display.y,
+            width: display.width,
+            height: display.height,
+            width_mm: display.width_mm,
+            height_mm: display.height_mm,
+            rotation: display.rotation,
+            scale_factor: display.scale_factor,
+            frequency: display.frequency,
+            is_primary: display.is_primary,
+            edid: display.edid.clone(),
+            hdr: display
+                .hdr
+                .or_else(|| parsed_edid.as_ref().and_then(HdrInfo::from_edid)),
+            stable_id,
+            modes: display.modes.clone(),
+            work_area: display.work_area.unwrap_or(Rect::new(
+                display.x,
+                display.y,
+                display.width,
+                display.height,
+            )),
+            work_area_size: display.work_area_size,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Fixture {
+    displays: Vec<MockDisplay>,
+}
+
+/// Display information from a fixed display layout.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MockBackend {
+    displays: Vec<MockDisplay>,
+}
+
+impl MockBackend {
+    pub fn new(displays: Vec<MockDisplay>) -> Self {
+        MockBackend { displays }
+    }
+
+    pub fn from_json(fixture: &str) -> DIResult<Self> {
+        let fixture: Fixture = serde_json::from_str(fixture).map_err(DIError::new)?;
+
+        Ok(MockBackend::new(fixture.displays))
+    }
+
+    pub fn from_toml(fixture: &str) -> DIResult<Self> {
+        let fixture: Fixture = toml::from_str(fixture).map_err(DIError::new)?;
+
+        Ok(MockBackend::new(fixture.displays))
+    }
+
+    /// Load a fixture file, parsed as TOML for a `.toml` extension and as JSON otherwise.
+    pub fn from_file(path: impl AsRef<Path>) -> DIResult<Self> {
+        let path = path.as_ref();
+        let fixture = fs::read_to_string(path)
+            .map_err(|err| DIError::new(format!("Read {} failed: {}", path.display(), err)))?;
+
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            MockBackend::from_toml(&fixture)
+        } else {
+            MockBackend::from_json(&fixture)
+        }
+    }
+
+    pub fn displays(&self) -> &[MockDisplay] {
+        &self.displays
+    }
+}
+
+impl DisplayBackend for MockBackend {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    fn get_all(&self) -> DIResult<Vec<DisplayInfo>> {
+        Ok(self.displays.iter().map(DisplayInfo::from).collect())
+    }
+}
@@ -0,0 +1,48 @@
+//! Portable representation of `ScreenRawHandle`, serialized as an integer on every platform.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::ScreenRawHandle;
+
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+pub(crate) fn to_u64(raw_handle: &ScreenRawHandle) -> u64 {
+    xcb::Xid::resource_id(raw_handle) as u64
+}
+
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+pub(crate) fn from_u64(value: u64) -> ScreenRawHandle {
+    xcb::XidNew::new(value as u32)
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn to_u64(raw_handle: &ScreenRawHandle) -> u64 {
+    *raw_handle as u64
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn from_u64(value: u64) -> ScreenRawHandle {
+    value as ScreenRawHandle
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn to_u64(raw_handle: &ScreenRawHandle) -> u64 {
+    raw_handle.0 as usize as u64
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn from_u64(value: u64) -> ScreenRawHandle {
+    windows::Win32::Graphics::Gdi::HMONITOR(value as usize as *mut _)
+}
+
+pub(crate) fn serialize<S: Serializer>(
+    raw_handle: &ScreenRawHandle,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    to_u64(raw_handle).serialize(serializer)
+}
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<ScreenRawHandle, D::Error> {
+    u64::deserialize(deserializer).map(from_u64)
+}
@@ -0,0 +1,304 @@
+//! All displays from a single enumeration, looked up in memory.
+//!
+//! Every [`DisplayInfo`] lookup asks the backend again, which opens a new X11 or Wayland
+//! connection each time. A [`DisplaySet`] enumerates the displays once and answers any number of
+//! lookups from that list.
+//!
+//! ```no_run
+//! use display_info::set::DisplaySet;
+//!
+//! let displays = DisplaySet::new().unwrap();
+//! let primary = displays.primary();
+//! let under_cursor = displays.at_point(100, 100);
+//!
+//! for display in &displays {
+//!     println!("{} at {:?}", display.name, display.bounds());
+//! }
+//! ```
+
+use std::sync::Arc;
+
+use crate::{
+    DisplayInfo, FromPointFallback,
+    backend::{self, DisplayBackend},
+    error::DIResult,
+    find_from_point, find_from_rect,
+    geometry::{Point, Rect},
+};
+
+/// The displays reported by a backend at one point in time.
+///
+/// Iteration goes from left to right, displays with the same x coordinate from top to bottom.
+/// Ties in [`DisplaySet::at_point_with`] and [`DisplaySet::for_rect`] still go to the first
+/// display in enumeration order, as with the backend lookups.
+#[derive(Debug, Clone, Default)]
+pub struct DisplaySet {
+    /// In enumeration order.
+    displays: Vec<DisplayInfo>,
+    /// Indices into `displays` in iteration order.
+    sorted: Vec<usize>,
+}
+
+impl DisplaySet {
+    /// Enumerate the displays of the [current backend](backend::current_backend).
+    pub fn new() -> DIResult<Self> {
+        DisplaySet::with_backend(backend::current_backend())
+    }
+
+    pub fn with_backend(backend: Arc<dyn DisplayBackend>) -> DIResult<Self> {
+        Ok(DisplaySet::from(backend.get_all()?))
+    }
+
+    pub fn len(&self) -> usize {
+        self.displays.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.displays.is_empty()
+    }
+
+    /// The displays sorted by position.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            displays: &self.displays,
+            sorted: self.sorted.iter(),
+        }
+    }
+
+    pub fn primary(&self) -> Option<&DisplayInfo> {
+        self.displays.iter().find(|d| d.is_primary)
+    }
+
+    pub fn by_id(&self, id: u32) -> Option<&DisplayInfo> {
+        self.displays.iter().find(|d| d.id == id)
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&DisplayInfo> {
+        self.displays.iter().find(|d| d.name == name)
+    }
+
+    /// The display containing the point, see [`DisplayInfo::from_point`].
+    pub fn at_point(&self, x: i32, y: i32) -> Option<&DisplayInfo> {
+        self.at_point_with(x, y, FromPointFallback::None)
+    }
+
+    /// The display containing the point or chosen by `fallback`, see
+    /// [`DisplayInfo::from_point_with`].
+    pub fn at_point_with(
+        &self,
+        x: i32,
+        y: i32,
+        fallback: FromPointFallback,
+    ) -> Option<&DisplayInfo> {
+        find_from_point(&self.displays, Point::new(x, y), fallback).map(|i| &self.displays[i])
+    }
+
+    /// The display a window at `x`, `y` of `width` by `height` pixels is on, see
+    /// [`DisplayInfo::from_rect`]. `None` only when the set is empty.
+    pub fn for_rect(&self, x: i32, y: i32, width: u32, height: u32) -> Option<&DisplayInfo> {
+        find_from_rect(&self.displays, &Rect::new(x, y, width, height)).map(|i| &self.displays[i])
+    }
+
+    /// The smallest rectangle containing all displays, empty when there are none.
+    pub fn virtual_bounds(&self) -> Rect {
+        self.displays
+            .iter()
+            .fold(Rect::default(), |bounds, d| bounds.union(&d.bounds()))
+    }
+
+    /// The displays in enumeration order.
+    pub fn into_vec(self) -> Vec<DisplayInfo> {
+        self.displays
+    }
+}
+
+impl From<Vec<DisplayInfo>> for DisplaySet {
+    fn from(displays: Vec<DisplayInfo>) -> Self {
+        let mut sorted: Vec<usize> = (0..displays.len()).collect();
+        sorted.sort_by_key(|&index| (displays[index].x, displays[index].y));
+
+        DisplaySet { displays, sorted }
+    }
+}
+
+impl<'a> IntoIterator for &'a DisplaySet {
+    type Item = &'a DisplayInfo;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the displays of a [`DisplaySet`], sorted by position.
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    displays: &'a [DisplayInfo],
+    sorted: std::slice::Iter<'a, usize>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a DisplayInfo;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sorted.next().map(|&index| &self.displays[index])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sorted.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.sorted.next_back().map(|&index| &self.displays[index])
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::display;
+
+    /// Serves the displays of a layout function, so queries outside the set see the same ones.
+    struct Layout(fn() -> Vec<DisplayInfo>);
+
+    impl DisplayBackend for Layout {
+        fn name(&self) -> &str {
+            "layout"
+        }
+
+        fn get_all(&self) -> DIResult<Vec<DisplayInfo>> {
+            Ok((self.0)())
+        }
+    }
+
+    /// A primary display with one to its left, one above it and one to its right that is 400
+    /// pixels lower, in no particular order.
+    fn layout() -> Vec<DisplayInfo> {
+        let mut primary = display(1, 0, 0, 2560, 1440);
+        primary.is_primary = true;
+
+        vec![
+            display(4, 2560, 400, 1920, 1080),
+            primary,
+            display(2, -1080, -200, 1080, 1920),
+            display(3, 0, -1080, 1920, 1080),
+        ]
+    }
+
+    fn ids<'a>(displays: impl Iterator<Item = &'a DisplayInfo>) -> Vec<u32> {
+        displays.map(|d| d.id).collect()
+    }
+
+    #[test]
+    fn iterates_left_to_right_then_top_to_bottom() {
+        let displays = DisplaySet::from(layout());
+
+        assert_eq!(ids(displays.iter()), [2, 3, 1, 4]);
+        assert_eq!(ids(displays.iter().rev()), [4, 1, 3, 2]);
+        assert_eq!(displays.iter().len(), 4);
+        assert_eq!(ids(displays.into_vec().iter()), [4, 1, 2, 3]);
+    }
+
+    #[test]
+    fn iterates_ties_in_enumeration_order() {
+        // mirrored displays share their position
+        let displays = DisplaySet::from(vec![
+            display(3, 1920, 0, 1920, 1080),
+            display(1, 0, 0, 1920, 1080),
+            display(2, 0, 0, 1920, 1080),
+            display(4, 1920, 0, 1280, 1024),
+        ]);
+
+        assert_eq!(ids(displays.iter()), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn virtual_bounds() {
+        let displays = DisplaySet::from(layout());
+
+        assert_eq!(
+            displays.virtual_bounds(),
+            Rect::new(-1080, -1080, 5560, 2800)
+        );
+        assert_eq!(DisplaySet::default().virtual_bounds(), Rect::default());
+        assert!(DisplaySet::default().is_empty());
+    }
+
+    #[test]
+    fn lookups() {
+        let displays = DisplaySet::from(layout());
+
+        assert_eq!(displays.by_id(3).map(|d| d.id), Some(3));
+        assert!(displays.by_id(5).is_none());
+        assert_eq!(displays.by_name("DP-2").map(|d| d.id), Some(2));
+        assert!(displays.by_name("DP-5").is_none());
+        assert_eq!(displays.primary().map(|d| d.id), Some(1));
+
+        let without_primary = DisplaySet::from(vec![display(1, 0, 0, 1920, 1080)]);
+        assert!(without_primary.primary().is_none());
+    }
+
+    #[test]
+    fn at_point_agrees_with_from_point() {
+        let _guard = backend::set_thread_backend(Layout(layout));
+        let displays = DisplaySet::new().unwrap();
+
+        let points = [
+            (0, 0),
+            (-1, -1),
+            (-1080, 1719),
+            (-1081, 0),
+            (2559, 399),
+            (2560, 400),
+            (3000, 100),
+            (4480, 1480),
+            (100, 1500),
+        ];
+
+        for (x, y) in points {
+            assert_eq!(
+                displays.at_point(x, y).map(|d| d.id),
+                DisplayInfo::from_point(x, y).ok().map(|d| d.id),
+                "{x}, {y}"
+            );
+
+            for fallback in [FromPointFallback::Nearest, FromPointFallback::Primary] {
+                assert_eq!(
+                    displays.at_point_with(x, y, fallback).map(|d| d.id),
+                    DisplayInfo::from_point_with(x, y, fallback)
+                        .ok()
+                        .map(|d| d.id),
+                    "{x}, {y}, {fallback:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn for_rect_agrees_with_from_rect() {
+        let _guard = backend::set_thread_backend(Layout(layout));
+        let displays = DisplaySet::new().unwrap();
+
+        let rects = [
+            (100, 100, 800, 600),
+            (2200, 300, 800, 600),
+            (-500, -500, 1000, 1000),
+            (3000, 0, 200, 200),
+            (-5000, 5000, 10, 10),
+        ];
+
+        for (x, y, width, height) in rects {
+            assert_eq!(
+                displays.for_rect(x, y, width, height).map(|d| d.id),
+                DisplayInfo::from_rect(x, y, width, height)
+                    .ok()
+                    .map(|d| d.id),
+                "{x}, {y}, {width}, {height}"
+            );
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! A versioned, serializable record of the display layout.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Deserializer, Serialize, de::Error};
+
+use crate::{DisplayInfo, backend::DisplayBackend, error::DIResult};
+
+/// The display layout reported by a backend at one point in time.
+///
+/// The serialized form is versioned by `schema_version`. Fields that later releases add to
+/// [`DisplayInfo`] deserialize with their default value, so older snapshots stay readable.
+/// Snapshots of a newer schema version than this release knows fail to deserialize.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplaySnapshot {
+    /// Version of the snapshot format, [`DisplaySnapshot::SCHEMA_VERSION`] when captured.
+    #[serde(deserialize_with = "deserialize_schema_version")]
+    pub schema_version: u32,
+    /// Name of the backend that reported the displays, e.g. `"xorg"`.
+    pub backend: String,
+    /// Milliseconds since the Unix epoch when the snapshot was taken.
+    pub timestamp: u64,
+    pub displays: Vec<DisplayInfo>,
+}
+
+impl DisplaySnapshot {
+    pub const SCHEMA_VERSION: u32 = 1;
+
+    pub fn new(backend: impl ToString, displays: Vec<DisplayInfo>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or_default();
+
+        DisplaySnapshot {
+            schema_version: DisplaySnapshot::SCHEMA_VERSION,
+            backend: backend.to_string(),
+            timestamp,
+            displays,
+        }
+    }
+
+    /// Take a snapshot from the current backend.
+    pub fn capture() -> DIResult<Self> {
+        DisplaySnapshot::from_backend(crate::backend::current_backend().as_ref())
+    }
+
+    pub fn from_backend(backend: &dyn DisplayBackend) -> DIResult<Self> {
+        Ok(DisplaySnapshot::new(backend.name(), backend.get_all()?))
+    }
+}
+
+fn deserialize_schema_version<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    let schema_version = u32::deserialize(deserializer)?;
+
+    if schema_version > DisplaySnapshot::SCHEMA_VERSION {
+        return Err(D::Error::custom(format!(
+            "snapshot schema version {schema_version} is newer than the supported version {}",
+            DisplaySnapshot::SCHEMA_VERSION
+        )));
+    }
+
+    Ok(schema_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use crate::{
+        DisplayMode, HdrInfo,
+        geometry::{Rect, Size},
+        raw_handle,
+        tests::display,
+    };
+
+    use super::*;
+
+    const V1: &str = include_str!("../tests/fixtures/snapshot/v1.json");
+
+    fn snapshot() -> DisplaySnapshot {
+        let mut display_info = display(66, -1080, -200, 1080, 1920);
+        display_info.name = "HDMI-1".to_string();
+        display_info.friendly_name = "LG FHD".to_string();
+        display_info.width_mm = 300;
+        display_info.height_mm = 530;
+        display_info.rotation = 90.0;
+        display_info.scale_factor = 1.5;
+        display_info.is_primary = true;
+        display_info.edid = Some(vec![0, 255, 255, 255, 255, 255, 255, 0]);
+        display_info.hdr = Some(HdrInfo {
+            sdr: true,
+            pq: true,
+            hlg: false,
+            max_luminance: Some(400.0),
+            max_frame_avg_luminance: Some(200.0),
+            min_luminance: None,
+            bt2020: true,
+        });
+        display_info.stable_id = "GSM-5B09-903NTPC1A234".to_string();
+        display_info.modes = vec![DisplayMode {
+            id: 0x4A,
+            width: 1920,
+            height: 1080,
+            refresh_rate: 60.0,
+            is_current: true,
+            is_preferred: true,
+            is_interlaced: false,
+            is_double_scan: false,
+        }];
+        display_info.work_area = Rect::new(-1080, -200, 1080, 1880);
+        display_info.work_area_size = Some(Size::new(1080, 1880));
+
+        DisplaySnapshot {
+            schema_version: 1,
+            backend: "xorg".to_string(),
+            timestamp: 1_700_000_000_000,
+            displays: vec![display_info],
+        }
+    }
+
+    #[test]
+    fn serializes_as_golden_file() {
+        let golden: Value = serde_json::from_str(V1).unwrap();
+
+        assert_eq!(serde_json::to_value(snapshot()).unwrap(), golden);
+        assert_eq!(golden["displays"][0]["raw_handle"], json!(66));
+    }
+
+    #[test]
+    fn deserializes_golden_file() {
+        let snapshot: DisplaySnapshot = serde_json::from_str(V1).unwrap();
+
+        assert_eq!(snapshot.schema_version, 1);
+        assert_eq!(snapshot.backend, "xorg");
+        assert_eq!(snapshot.timestamp, 1_700_000_000_000);
+
+        let display_info = &snapshot.displays[0];
+        assert_eq!(display_info.id, 66);
+        assert_eq!(raw_handle::to_u64(&display_info.raw_handle), 66);
+        assert_eq!(display_info.bounds(), Rect::new(-1080, -200, 1080, 1920));
+        assert_eq!(display_info.hdr.unwrap().max_luminance, Some(400.0));
+        assert_eq!(display_info.modes[0].id, 0x4A);
+        assert_eq!(display_info.work_area_size, Some(Size::new(1080, 1880)));
+    }
+
+    #[test]
+    fn missing_fields_take_their_default() {
+        let mut golden: Value = serde_json::from_str(V1).unwrap();
+        let display_info = golden["displays"][0].as_object_mut().unwrap();
+        for field in [
+            "edid",
+            "hdr",
+            "stable_id",
+            "modes",
+            "work_area",
+            "work_area_size",
+        ] {
+            display_info.remove(field).unwrap();
+        }
+
+        let snapshot: DisplaySnapshot = serde_json::from_value(golden).unwrap();
+        let display_info = &snapshot.displays[0];
+        assert_eq!(display_info.edid, None);
+        assert_eq!(display_info.hdr, None);
+        assert_eq!(display_info.stable_id, "");
+        assert!(display_info.modes.is_empty());
+        assert_eq!(display_info.work_area, Rect::default());
+        assert_eq!(display_info.work_area_size, None);
+    }
+
+    #[test]
+    fn rejects_newer_schema_versions() {
+        let mut golden: Value = serde_json::from_str(V1).unwrap();
+        golden["schema_version"] = json!(DisplaySnapshot::SCHEMA_VERSION + 1);
+
+        let err = serde_json::from_value::<DisplaySnapshot>(golden).unwrap_err();
+        assert!(err.to_string().contains("schema version 2"), "{err}");
+    }
+
+    #[test]
+    fn display_info_round_trip() {
+        let display_info = snapshot().displays.remove(0);
+
+        let json = serde_json::to_string(&display_info).unwrap();
+        let round_trip: DisplayInfo = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&round_trip).unwrap(),
+            serde_json::to_value(&display_info).unwrap()
+        );
+        assert_eq!(
+            raw_handle::to_u64(&round_trip.raw_handle),
+            raw_handle::to_u64(&display_info.raw_handle)
+        );
+        assert_eq!(round_trip.edid, display_info.edid);
+        assert_eq!(round_trip.hdr, display_info.hdr);
+        assert_eq!(round_trip.modes, display_info.modes);
+    }
+}
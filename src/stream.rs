@@ -0,0 +1,79 @@
+//! Display change events as a [`Stream`], driven by the tokio reactor.
+//!
+//! Every stream opens an X11 or Wayland connection of its own, even on a
+//! [`DisplayContext`](crate::backend::DisplayContext), and registers it with the reactor, no
+//! thread is started for it. Must be created from within a tokio runtime.
+
+use std::{
+    collections::VecDeque,
+    os::fd::RawFd,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, ready},
+};
+
+use futures_core::Stream;
+use tokio::io::unix::AsyncFd;
+
+use crate::{
+    backend::{self, DisplayBackend},
+    error::{DIError, DIResult},
+    watcher::{DisplayEvent, DisplayEventSource},
+};
+
+/// Asynchronous counterpart of [`DisplayWatcher`](crate::watcher::DisplayWatcher).
+pub struct DisplayEventStream {
+    fd: AsyncFd<RawFd>,
+    source: Box<dyn DisplayEventSource>,
+    events: VecDeque<DisplayEvent>,
+}
+
+impl DisplayEventStream {
+    /// Watch the displays of the [current backend](backend::current_backend).
+    pub fn new() -> DIResult<Self> {
+        DisplayEventStream::with_backend(backend::current_backend())
+    }
+
+    pub fn with_backend(backend: Arc<dyn DisplayBackend>) -> DIResult<Self> {
+        let source = backend.watch()?;
+        let fd = source
+            .as_raw_fd()
+            .ok_or(DIError::Unsupported("DisplayEventStream"))?;
+
+        Ok(DisplayEventStream {
+            fd: AsyncFd::new(fd).map_err(DIError::new)?,
+            source,
+            events: VecDeque::new(),
+        })
+    }
+}
+
+impl Stream for DisplayEventStream {
+    type Item = DIResult<DisplayEvent>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(event) = this.events.pop_front() {
+                return Poll::Ready(Some(Ok(event)));
+            }
+
+            // the connection may already hold events read while waiting for a reply,
+            // so check before waiting for the fd
+            match this.source.try_next_events() {
+                Ok(events) if !events.is_empty() => {
+                    this.events.extend(events);
+                    continue;
+                }
+                Ok(_) => {}
+                Err(err) => return Poll::Ready(Some(Err(err))),
+            }
+
+            match ready!(this.fd.poll_read_ready(cx)) {
+                Ok(mut guard) => guard.clear_ready(),
+                Err(err) => return Poll::Ready(Some(Err(DIError::new(err)))),
+            }
+        }
+    }
+}
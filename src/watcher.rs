@@ -0,0 +1,429 @@
+//! Notifications of display hotplug and configuration changes.
+//!
+//! ```no_run
+//! use display_info::watcher::{DisplayEvent, DisplayWatcher};
+//!
+//! for event in DisplayWatcher::new().unwrap() {
+//!     match event.unwrap() {
+//!         DisplayEvent::Added(display_info) => println!("added {}", display_info.name),
+//!         DisplayEvent::Removed(display_info) => println!("removed {}", display_info.name),
+//!         event => println!("{event:?}"),
+//!     }
+//! }
+//! ```
+
+#[cfg(all(feature = "async", target_family = "unix"))]
+use std::os::fd::RawFd;
+use std::{collections::VecDeque, sync::Arc};
+
+#[cfg(all(feature = "async", target_family = "unix"))]
+use crate::error::DIError;
+use crate::{
+    DisplayInfo,
+    backend::{self, DisplayBackend},
+    diff::{DisplayChange, DisplayDiff, FieldChange, is_same_display},
+    error::DIResult,
+};
+
+/// A change of the display configuration, carrying the display as it is after the change.
+#[derive(Debug, Clone)]
+pub enum DisplayEvent {
+    /// A display was connected or enabled.
+    Added(DisplayInfo),
+    /// A display was disconnected or disabled. Carries the display as it was last seen.
+    Removed(DisplayInfo),
+    /// The display position changed.
+    Moved(DisplayInfo),
+    /// The display pixel size changed.
+    Resized(DisplayInfo),
+    /// The display rotation changed.
+    Rotated(DisplayInfo),
+    /// The display became the primary display.
+    PrimaryChanged(DisplayInfo),
+    /// The display switched to another mode or refresh rate.
+    ModeChanged(DisplayInfo),
+    /// Another property of the display changed, e.g. its scale factor or name.
+    Changed(DisplayInfo),
+}
+
+/// Backend subscription to display configuration changes, see [`DisplayBackend::watch`].
+pub trait DisplayEventSource: Send {
+    /// Block until the display configuration changed and return the changes.
+    fn next_events(&mut self) -> DIResult<Vec<DisplayEvent>>;
+
+    /// File descriptor of the connection, readable when
+    /// [`try_next_events`](DisplayEventSource::try_next_events) may have changes to return.
+    /// The connection must belong to the source alone: nothing else may read from it, and
+    /// [`DisplayEventStream`](crate::stream::DisplayEventStream) registers it with the reactor.
+    #[cfg(all(feature = "async", target_family = "unix"))]
+    fn as_raw_fd(&self) -> Option<RawFd> {
+        None
+    }
+
+    /// Return the changes received so far without blocking, possibly none.
+    #[cfg(all(feature = "async", target_family = "unix"))]
+    fn try_next_events(&mut self) -> DIResult<Vec<DisplayEvent>> {
+        Err(DIError::Unsupported("try_next_events"))
+    }
+}
+
+/// Watches the displays of a backend and reports their changes as [`DisplayEvent`]s.
+///
+/// Iterating the watcher blocks until the next event.
+pub struct DisplayWatcher {
+    source: Box<dyn DisplayEventSource>,
+    displays: Vec<DisplayInfo>,
+    events: VecDeque<DisplayEvent>,
+}
+
+impl DisplayWatcher {
+    /// Watch the displays of the [current backend](backend::current_backend).
+    pub fn new() -> DIResult<Self> {
+        DisplayWatcher::with_backend(backend::current_backend())
+    }
+
+    pub fn with_backend(backend: Arc<dyn DisplayBackend>) -> DIResult<Self> {
+        // subscribe before taking the first snapshot so no change falls in between
+        let source = backend.watch()?;
+        let displays = backend.get_all()?;
+
+        Ok(DisplayWatcher {
+            source,
+            displays,
+            events: VecDeque::new(),
+        })
+    }
+
+    /// The displays as of the last reported event.
+    pub fn displays(&self) -> &[DisplayInfo] {
+        &self.displays
+    }
+
+    /// Block until the next event.
+    pub fn next_event(&mut self) -> DIResult<DisplayEvent> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(event);
+            }
+
+            for event in self.source.next_events()? {
+                self.apply(&event);
+                self.events.push_back(event);
+            }
+        }
+    }
+
+    fn apply(&mut self, event: &DisplayEvent) {
+        match event {
+            DisplayEvent::Added(display_info) => self.displays.push(display_info.clone()),
+            DisplayEvent::Removed(display_info) => {
+                self.displays.retain(|d| !is_same_display(d, display_info))
+            }
+            DisplayEvent::Moved(display_info)
+            | DisplayEvent::Resized(display_info)
+            | DisplayEvent::Rotated(display_info)
+            | DisplayEvent::PrimaryChanged(display_info)
+            | DisplayEvent::ModeChanged(display_info)
+            | DisplayEvent::Changed(display_info) => {
+                if let Some(d) = self
+                    .displays
+                    .iter_mut()
+                    .find(|d| is_same_display(d, display_info))
+                {
+                    *d = display_info.clone();
+                }
+            }
+        }
+    }
+
+    /// Call `callback` with every event until watching fails.
+    pub fn run<F: FnMut(DisplayEvent)>(mut self, mut callback: F) -> DIResult<()> {
+        loop {
+            callback(self.next_event()?);
+        }
+    }
+}
+
+impl Iterator for DisplayWatcher {
+    type Item = DIResult<DisplayEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_event())
+    }
+}
+
+/// Events for the changes of one display.
+pub(crate) fn get_display_events(change: &DisplayChange) -> Vec<DisplayEvent> {
+    let display_info = &change.new;
+    let mut events = Vec::new();
+    let mut is_mode_changed = false;
+    let mut is_other_changed = false;
+
+    for field_change in &change.changes {
+        match field_change {
+            FieldChange::Position { .. } => events.push(DisplayEvent::Moved(display_info.clone())),
+            FieldChange::Size { .. } => events.push(DisplayEvent::Resized(display_info.clone())),
+            FieldChange::Rotation { .. } => {
+                events.push(DisplayEvent::Rotated(display_info.clone()))
+            }
+            FieldChange::Primary { new: true, .. } => {
+                events.push(DisplayEvent::PrimaryChanged(display_info.clone()))
+            }
+            FieldChange::Frequency { .. } | FieldChange::CurrentMode { .. } => {
+                is_mode_changed = true
+            }
+            _ => is_other_changed = true,
+        }
+    }
+
+    if is_mode_changed {
+        events.push(DisplayEvent::ModeChanged(display_info.clone()));
+    }
+    if events.is_empty() && is_other_changed {
+        events.push(DisplayEvent::Changed(display_info.clone()));
+    }
+
+    events
+}
+
+/// Events for the changes of a display list.
+pub(crate) fn get_events(diff: &DisplayDiff) -> Vec<DisplayEvent> {
+    let removed = diff.removed.iter().cloned().map(DisplayEvent::Removed);
+    let added = diff.added.iter().cloned().map(DisplayEvent::Added);
+    let changed = diff.changed.iter().flat_map(get_display_events);
+
+    removed.chain(added).chain(changed).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DisplayMode, geometry::Rect, tests::display};
+
+    use super::*;
+
+    fn events(old: DisplayInfo, new: DisplayInfo) -> Vec<(&'static str, u32)> {
+        kinds(&get_events(&DisplayDiff::new(&[old], &[new])))
+    }
+
+    fn kinds(events: &[DisplayEvent]) -> Vec<(&'static str, u32)> {
+        events
+            .iter()
+            .map(|event| match event {
+                DisplayEvent::Added(d) => ("added", d.id),
+                DisplayEvent::Removed(d) => ("removed", d.id),
+                DisplayEvent::Moved(d) => ("moved", d.id),
+                DisplayEvent::Resized(d) => ("resized", d.id),
+                DisplayEvent::Rotated(d) => ("rotated", d.id),
+                DisplayEvent::PrimaryChanged(d) => ("primary", d.id),
+                DisplayEvent::ModeChanged(d) => ("mode", d.id),
+                DisplayEvent::Changed(d) => ("changed", d.id),
+            })
+            .collect()
+    }
+
+    fn with_modes(mut display: DisplayInfo, current: u32) -> DisplayInfo {
+        display.modes = (1..=2)
+            .map(|id| DisplayMode {
+                id,
+                width: 1920,
+                height: 1080,
+                refresh_rate: 60.0 * id as f32,
+                is_current: id == current,
+                ..Default::default()
+            })
+            .collect();
+        display
+    }
+
+    #[test]
+    fn events_per_field() {
+        let old = || display(1, 0, 0, 1920, 1080);
+
+        assert_eq!(
+            events(old(), display(1, 1920, 0, 1920, 1080)),
+            [("moved", 1)]
+        );
+        assert_eq!(
+            events(old(), display(1, 0, 0, 2560, 1440)),
+            [("resized", 1)]
+        );
+        assert_eq!(
+            events(
+                old(),
+                DisplayInfo {
+                    rotation: 90.0,
+                    ..old()
+                }
+            ),
+            [("rotated", 1)]
+        );
+        assert_eq!(
+            events(
+                old(),
+                DisplayInfo {
+                    is_primary: true,
+                    ..old()
+                }
+            ),
+            [("primary", 1)]
+        );
+        assert_eq!(
+            events(
+                old(),
+                DisplayInfo {
+                    frequency: 144.0,
+                    ..old()
+                }
+            ),
+            [("mode", 1)]
+        );
+        assert_eq!(
+            events(with_modes(old(), 1), with_modes(old(), 2)),
+            [("mode", 1)]
+        );
+        assert_eq!(
+            events(
+                old(),
+                DisplayInfo {
+                    scale_factor: 2.0,
+                    ..old()
+                }
+            ),
+            [("changed", 1)]
+        );
+        assert_eq!(
+            events(
+                old(),
+                DisplayInfo {
+                    friendly_name: "LG FHD".to_string(),
+                    ..old()
+                }
+            ),
+            [("changed", 1)]
+        );
+    }
+
+    #[test]
+    fn losing_primary_is_a_change() {
+        let old = DisplayInfo {
+            is_primary: true,
+            ..display(1, 0, 0, 1920, 1080)
+        };
+
+        assert_eq!(events(old, display(1, 0, 0, 1920, 1080)), [("changed", 1)]);
+    }
+
+    #[test]
+    fn events_for_several_fields() {
+        let old = with_modes(display(1, 0, 0, 1920, 1080), 1);
+        let new = DisplayInfo {
+            frequency: 120.0,
+            scale_factor: 1.5,
+            ..with_modes(display(1, 1920, 0, 2560, 1440), 2)
+        };
+
+        // one mode change for both the frequency and the mode, no change for the rest
+        assert_eq!(
+            events(old, new),
+            [("moved", 1), ("resized", 1), ("mode", 1)]
+        );
+    }
+
+    #[test]
+    fn events_for_display_lists() {
+        let old = [
+            display(1, 0, 0, 1920, 1080),
+            display(2, 1920, 0, 1920, 1080),
+        ];
+        let new = [
+            display(2, 0, 0, 1920, 1080),
+            display(3, 1920, 0, 1920, 1080),
+        ];
+
+        assert_eq!(
+            kinds(&get_events(&DisplayDiff::new(&old, &new))),
+            [("removed", 1), ("added", 3), ("moved", 2)]
+        );
+        assert!(get_events(&DisplayDiff::new(&old, &old)).is_empty());
+    }
+
+    /// The display lists of a backend over time, each watch event moving to the next one.
+    const STEPS: [fn() -> Vec<DisplayInfo>; 3] = [
+        || {
+            vec![
+                display(1, 0, 0, 1920, 1080),
+                display(2, 1920, 0, 1920, 1080),
+            ]
+        },
+        || {
+            vec![
+                DisplayInfo {
+                    is_primary: true,
+                    ..display(1, 0, 0, 1920, 1080)
+                },
+                display(2, 1920, 0, 2560, 1440),
+            ]
+        },
+        || {
+            vec![
+                display(2, 0, 0, 2560, 1440),
+                display(3, 2560, 0, 1920, 1080),
+            ]
+        },
+    ];
+
+    struct Steps;
+
+    impl DisplayBackend for Steps {
+        fn name(&self) -> &str {
+            "steps"
+        }
+
+        fn get_all(&self) -> DIResult<Vec<DisplayInfo>> {
+            Ok(STEPS[0]())
+        }
+
+        fn watch(&self) -> DIResult<Box<dyn DisplayEventSource>> {
+            Ok(Box::new(StepSource(0)))
+        }
+    }
+
+    struct StepSource(usize);
+
+    impl DisplayEventSource for StepSource {
+        fn next_events(&mut self) -> DIResult<Vec<DisplayEvent>> {
+            let step = &mut self.0;
+            let old = STEPS[*step]();
+            *step += 1;
+
+            Ok(get_events(&DisplayDiff::new(&old, &STEPS[*step]())))
+        }
+    }
+
+    fn layout(displays: &[DisplayInfo]) -> Vec<(u32, Rect, bool)> {
+        let mut layout: Vec<_> = displays
+            .iter()
+            .map(|d| (d.id, d.bounds(), d.is_primary))
+            .collect();
+        layout.sort_by_key(|&(id, ..)| id);
+        layout
+    }
+
+    #[test]
+    fn watcher_follows_events() {
+        let mut watcher = DisplayWatcher::with_backend(Arc::new(Steps)).unwrap();
+        assert_eq!(layout(watcher.displays()), layout(&STEPS[0]()));
+
+        let mut events = vec![watcher.next_event().unwrap()];
+        // the displays are updated for all events received at once
+        assert_eq!(layout(watcher.displays()), layout(&STEPS[1]()));
+        events.push(watcher.next_event().unwrap());
+        assert_eq!(kinds(&events), [("primary", 1), ("resized", 2)]);
+
+        let events = (0..3)
+            .map(|_| watcher.next_event().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(kinds(&events), [("removed", 1), ("added", 3), ("moved", 2)]);
+        assert_eq!(layout(watcher.displays()), layout(&STEPS[2]()));
+    }
+}
@@ -5,26 +5,27 @@ use utils::{get_dev_mode_w, get_display_friendly_name, get_scale_factor, monitor
 use widestring::U16CString;
 use windows::{
     Win32::{
-        Foundation::{LPARAM, POINT},
+        Foundation::LPARAM,
         Graphics::Gdi::{
             CreateDCW, DMDO_90, DMDO_180, DMDO_270, DMDO_DEFAULT, DeleteDC, EnumDisplayMonitors,
-            GetDeviceCaps, GetMonitorInfoW, HMONITOR, HORZSIZE, MONITOR_DEFAULTTONULL, MONITORINFO,
-            MONITORINFOEXW, MonitorFromPoint, VERTSIZE,
+            GetDeviceCaps, GetMonitorInfoW, HMONITOR, HORZSIZE, MONITORINFO, MONITORINFOEXW,
+            VERTSIZE,
         },
         UI::WindowsAndMessaging::MONITORINFOF_PRIMARY,
     },
     core::PCWSTR,
 };
 
-use crate::{
-    DisplayInfo,
-    error::{DIError, DIResult},
-};
+use crate::{DisplayInfo, backend::DisplayBackend, error::DIResult, geometry::Rect, stable_id};
 
 mod utils;
 
 pub type ScreenRawHandle = HMONITOR;
 
+/// Display information from the Win32 monitor APIs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WindowsBackend;
+
 impl DisplayInfo {
     fn new(h_monitor: HMONITOR) -> DIResult<DisplayInfo> {
         let mut monitor_info_ex_w = MONITORINFOEXW::default();
@@ -78,8 +79,13 @@ impl DisplayInfo {
 
         let scale_factor = get_scale_factor(h_monitor, scope_guard_hdc)?;
 
+        let rc_monitor = monitor_info_ex_w.monitorInfo.rcMonitor;
+        let rc_work = monitor_info_ex_w.monitorInfo.rcWork;
+        let bounds = Rect::new(dm_position.x, dm_position.y, dm_pels_width, dm_pels_height);
+
         Ok(DisplayInfo {
             id: h_monitor.0 as u32,
+            stable_id: stable_id(None, &name),
             name,
             friendly_name: get_display_friendly_name(monitor_info_ex_w)
                 .unwrap_or(format!("Unknown Display {}", h_monitor.0 as u32)),
@@ -94,10 +100,52 @@ impl DisplayInfo {
             scale_factor,
             frequency: dev_mode_w.dmDisplayFrequency as f32,
             is_primary: monitor_info_ex_w.monitorInfo.dwFlags == MONITORINFOF_PRIMARY,
+            edid: None,
+            hdr: None,
+            modes: Vec::new(),
+            work_area: get_work_area(
+                bounds,
+                Rect::from_edges(
+                    rc_monitor.left,
+                    rc_monitor.top,
+                    rc_monitor.right,
+                    rc_monitor.bottom,
+                ),
+                Rect::from_edges(rc_work.left, rc_work.top, rc_work.right, rc_work.bottom),
+            ),
+            work_area_size: None,
         })
     }
+}
+
+/// Move the work area into the physical coordinates of the display bounds.
+///
+/// `GetMonitorInfoW` virtualizes its rectangles for processes that aren't per-monitor DPI
+/// aware, while the bounds come from the display mode. The insets of the work area are scaled
+/// from the monitor rectangle to the bounds and applied there.
+fn get_work_area(bounds: Rect, monitor: Rect, work: Rect) -> Rect {
+    if monitor.is_empty() {
+        return bounds;
+    }
 
-    pub fn all() -> DIResult<Vec<DisplayInfo>> {
+    let scale_x = bounds.width as f64 / monitor.width as f64;
+    let scale_y = bounds.height as f64 / monitor.height as f64;
+    let scale = |inset: i32, scale: f64| (inset as f64 * scale).round() as i32;
+
+    Rect::from_edges(
+        bounds.x + scale(work.x - monitor.x, scale_x),
+        bounds.y + scale(work.y - monitor.y, scale_y),
+        bounds.right() - scale(monitor.right() - work.right(), scale_x),
+        bounds.bottom() - scale(monitor.bottom() - work.bottom(), scale_y),
+    )
+}
+
+impl DisplayBackend for WindowsBackend {
+    fn name(&self) -> &str {
+        "windows"
+    }
+
+    fn get_all(&self) -> DIResult<Vec<DisplayInfo>> {
         let h_monitors_mut_ptr: *mut Vec<HMONITOR> = Box::into_raw(Box::default());
 
         let h_monitors = unsafe {
@@ -123,15 +171,46 @@ impl DisplayInfo {
 
         Ok(impl_monitors)
     }
+}
 
-    pub fn from_point(x: i32, y: i32) -> DIResult<DisplayInfo> {
-        let point = POINT { x, y };
-        let h_monitor = unsafe { MonitorFromPoint(point, MONITOR_DEFAULTTONULL) };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        if h_monitor.is_invalid() {
-            return Err(DIError::new("Not found monitor"));
-        }
+    #[test]
+    fn work_area_per_monitor_aware() {
+        let bounds = Rect::new(-1920, 0, 1920, 1080);
+
+        assert_eq!(
+            get_work_area(bounds, bounds, Rect::new(-1920, 0, 1920, 1040)),
+            Rect::new(-1920, 0, 1920, 1040)
+        );
+    }
+
+    #[test]
+    fn work_area_dpi_virtualized() {
+        // a 150% display seen by a process that isn't DPI aware
+        let bounds = Rect::new(2560, 0, 3840, 2160);
+        let monitor = Rect::new(1707, 0, 2560, 1440);
+        let work = Rect::new(1707, 0, 2560, 1400);
 
-        DisplayInfo::new(h_monitor)
+        assert_eq!(
+            get_work_area(bounds, monitor, work),
+            Rect::new(2560, 0, 3840, 2100)
+        );
+        assert_eq!(
+            get_work_area(bounds, monitor, Rect::new(1747, 20, 2520, 1420)),
+            Rect::new(2620, 30, 3780, 2130)
+        );
+    }
+
+    #[test]
+    fn work_area_without_monitor_rect() {
+        let bounds = Rect::new(0, 0, 1920, 1080);
+
+        assert_eq!(
+            get_work_area(bounds, Rect::default(), Rect::default()),
+            bounds
+        );
     }
 }
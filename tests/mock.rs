@@ -0,0 +1,116 @@
+//! Display queries answered by the mock backend from the fixtures in `tests/fixtures/mock`.
+
+#![cfg(feature = "mock")]
+
+use display_info::{
+    DisplayInfo, FromPointFallback,
+    backend::{self, ThreadBackendGuard},
+    geometry::Rect,
+    mock::MockBackend,
+};
+
+const JSON_FIXTURE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/mock/displays.json"
+);
+const TOML_FIXTURE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/mock/displays.toml"
+);
+
+fn use_fixture(path: &str) -> ThreadBackendGuard {
+    backend::set_thread_backend(MockBackend::from_file(path).unwrap())
+}
+
+#[test]
+fn json_and_toml_fixtures_match() {
+    assert_eq!(
+        MockBackend::from_file(JSON_FIXTURE).unwrap(),
+        MockBackend::from_file(TOML_FIXTURE).unwrap()
+    );
+}
+
+#[test]
+fn all() {
+    for fixture in [JSON_FIXTURE, TOML_FIXTURE] {
+        let _guard = use_fixture(fixture);
+
+        let display_infos = DisplayInfo::all().unwrap();
+        let names: Vec<&str> = display_infos.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["DP-1", "HDMI-1", "DP-2"], "{fixture}");
+
+        let [dp1, hdmi1, dp2] = &display_infos[..] else {
+            unreachable!();
+        };
+
+        assert!(dp1.is_primary && !hdmi1.is_primary && !dp2.is_primary);
+        assert_eq!(dp1.friendly_name, "LG HDR 4K");
+        assert_eq!((dp1.width_mm, dp1.height_mm), (600, 340));
+        assert_eq!((dp1.scale_factor, dp1.frequency), (2.0, 144.0));
+        assert_eq!(dp1.work_area, Rect::new(0, 0, 2560, 1400));
+
+        // omitted fields take their defaults
+        assert_eq!(hdmi1.bounds(), Rect::new(-1080, -200, 1080, 1920));
+        assert_eq!(hdmi1.rotation, 90.0);
+        assert_eq!(hdmi1.friendly_name, "HDMI-1");
+        assert_eq!(hdmi1.scale_factor, 1.0);
+        assert_eq!(hdmi1.frequency, 60.0);
+        assert_eq!(hdmi1.work_area, hdmi1.bounds());
+        assert_eq!(hdmi1.stable_id, "HDMI-1");
+        assert!(hdmi1.edid.is_none() && hdmi1.modes.is_empty());
+
+        assert_eq!(dp2.stable_id, "GSM-5B09-903NTPC1A234");
+    }
+}
+
+#[test]
+fn from_point() {
+    let _guard = use_fixture(TOML_FIXTURE);
+
+    let name = |x, y| DisplayInfo::from_point(x, y).map(|d| d.name);
+
+    assert_eq!(name(0, 0).unwrap(), "DP-1");
+    assert_eq!(name(2559, 1439).unwrap(), "DP-1");
+    assert_eq!(name(-1, 0).unwrap(), "HDMI-1");
+    assert_eq!(name(-1080, -200).unwrap(), "HDMI-1");
+    assert_eq!(name(2560, 0).unwrap(), "DP-2");
+    assert!(name(0, -1).is_err());
+    assert!(name(3000, 1200).is_err());
+
+    let nearest = DisplayInfo::from_point_with(3000, 1200, FromPointFallback::Nearest).unwrap();
+    assert_eq!(nearest.name, "DP-2");
+    let primary = DisplayInfo::from_point_with(0, -1, FromPointFallback::Primary).unwrap();
+    assert_eq!(primary.name, "DP-1");
+}
+
+#[test]
+fn from_name() {
+    let _guard = use_fixture(JSON_FIXTURE);
+
+    assert_eq!(DisplayInfo::from_name("HDMI-1").unwrap().id, 2);
+    assert_eq!(DisplayInfo::from_name("DP-2").unwrap().id, 3);
+    assert!(DisplayInfo::from_name("eDP-1").is_err());
+    assert_eq!(DisplayInfo::primary().unwrap().name, "DP-1");
+}
+
+#[test]
+fn thread_backends_nest() {
+    let _outer = use_fixture(JSON_FIXTURE);
+    assert_eq!(DisplayInfo::all().unwrap().len(), 3);
+
+    {
+        let _inner = backend::set_thread_backend(MockBackend::default());
+        assert!(DisplayInfo::all().unwrap().is_empty());
+
+        // other threads don't see the backend of this one
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let _guard = use_fixture(TOML_FIXTURE);
+                assert_eq!(DisplayInfo::all().unwrap().len(), 3);
+            });
+        });
+        assert!(DisplayInfo::all().unwrap().is_empty());
+    }
+
+    assert_eq!(DisplayInfo::all().unwrap().len(), 3);
+}
@@ -0,0 +1,43 @@
+//! The mock backend picked up from `DISPLAY_INFO_MOCK_FIXTURE`. The environment is process-wide,
+//! so this is the only test of its binary.
+
+#![cfg(feature = "mock")]
+
+use std::{env, fs};
+
+use display_info::{DisplayInfo, mock::MOCK_FIXTURE_ENV};
+
+#[test]
+fn fixture_from_env() {
+    let fixture = env::temp_dir().join(format!("display-info-{}.toml", std::process::id()));
+    fs::copy(
+        concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/mock/displays.toml"
+        ),
+        &fixture,
+    )
+    .unwrap();
+
+    // SAFETY: no other thread of this binary reads or writes the environment
+    unsafe { env::set_var(MOCK_FIXTURE_ENV, &fixture) };
+
+    let names = |display_infos: Vec<DisplayInfo>| {
+        display_infos
+            .into_iter()
+            .map(|d| d.name)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names(DisplayInfo::all().unwrap()),
+        ["DP-1", "HDMI-1", "DP-2"]
+    );
+
+    // the fixture is loaded once, not on every query
+    fs::remove_file(&fixture).unwrap();
+    assert_eq!(
+        names(DisplayInfo::all().unwrap()),
+        ["DP-1", "HDMI-1", "DP-2"]
+    );
+    assert_eq!(DisplayInfo::from_point(-1, 0).unwrap().name, "HDMI-1");
+}
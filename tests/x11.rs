@@ -0,0 +1,163 @@
+//! Display configuration changes against a live X server.
+//!
+//! These change the configuration of the X server in `DISPLAY`, so they are ignored by default.
+//! Run them against Xvfb:
+//!
+//! ```sh
+//! Xvfb :99 -screen 0 1920x1080x24 &
+//! DISPLAY=:99 cargo test --test x11 -- --ignored
+//! ```
+//!
+//! Every test restores the configuration it changed.
+
+#![cfg(all(target_family = "unix", not(target_os = "macos")))]
+
+use std::{
+    env,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use display_info::{
+    DisplayInfo, DisplayMode, Reflection,
+    backend::{DisplayBackend, XorgBackend},
+    error::DIError,
+};
+
+/// The tests reconfigure the same X server, so they run one at a time.
+static X_SERVER: Mutex<()> = Mutex::new(());
+
+/// The displays of the X server, `None` when there is none to test against.
+fn displays() -> Option<(MutexGuard<'static, ()>, Vec<DisplayInfo>)> {
+    if env::var_os("DISPLAY").is_none() {
+        eprintln!("DISPLAY is not set, skipping");
+        return None;
+    }
+
+    let guard = X_SERVER.lock().unwrap_or_else(PoisonError::into_inner);
+
+    Some((guard, XorgBackend.get_all().unwrap()))
+}
+
+fn current_mode(display: &DisplayInfo) -> Option<DisplayMode> {
+    display.modes.iter().find(|m| m.is_current).copied()
+}
+
+#[test]
+#[ignore = "changes the configuration of the X server in DISPLAY"]
+fn set_mode_round_trip() {
+    let Some((_guard, displays)) = displays() else {
+        return;
+    };
+
+    for display in displays {
+        let Some(current) = current_mode(&display) else {
+            continue;
+        };
+        let _restore = scopeguard::guard((), |_| {
+            XorgBackend.set_mode(&display, &current).unwrap();
+        });
+
+        XorgBackend.set_mode(&display, &current).unwrap();
+        let unchanged = XorgBackend.get_from_name(&display.name).unwrap();
+        assert_eq!(current_mode(&unchanged).map(|m| m.id), Some(current.id));
+
+        if let Some(other) = display.modes.iter().find(|m| !m.is_current) {
+            XorgBackend.set_mode(&display, other).unwrap();
+
+            let changed = XorgBackend.get_from_name(&display.name).unwrap();
+            assert_eq!(current_mode(&changed).map(|m| m.id), Some(other.id));
+        }
+    }
+}
+
+#[test]
+#[ignore = "changes the configuration of the X server in DISPLAY"]
+fn set_mode_rejects_unknown_mode() {
+    let Some((_guard, displays)) = displays() else {
+        return;
+    };
+
+    for display in displays {
+        let mode = DisplayMode {
+            id: u32::MAX,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            XorgBackend.set_mode(&display, &mode),
+            Err(DIError::InvalidMode(u32::MAX))
+        ));
+    }
+}
+
+#[test]
+#[ignore = "changes the configuration of the X server in DISPLAY"]
+fn set_primary_round_trip() {
+    let Some((_guard, displays)) = displays() else {
+        return;
+    };
+    let Some(primary) = displays.iter().find(|d| d.is_primary) else {
+        eprintln!("no primary display, skipping");
+        return;
+    };
+    let _restore = scopeguard::guard((), |_| {
+        XorgBackend.set_primary(primary).unwrap();
+    });
+
+    for display in &displays {
+        XorgBackend.set_primary(display).unwrap();
+
+        let primary = XorgBackend.get_primary().unwrap();
+        assert_eq!(primary.name, display.name);
+        assert_eq!(
+            XorgBackend
+                .get_all()
+                .unwrap()
+                .iter()
+                .filter(|d| d.is_primary)
+                .count(),
+            1
+        );
+    }
+}
+
+#[test]
+#[ignore = "changes the configuration of the X server in DISPLAY"]
+fn set_rotation_round_trip() {
+    let Some((_guard, displays)) = displays() else {
+        return;
+    };
+
+    for display in displays {
+        let _restore = scopeguard::guard((), |_| {
+            XorgBackend
+                .set_rotation(&display, display.rotation, Reflection::default())
+                .unwrap();
+        });
+
+        // not every CRTC supports every rotation, e.g. Xvfb only rotates by 0
+        match XorgBackend.set_rotation(&display, 180.0, Reflection::default()) {
+            Ok(()) => {
+                let rotated = XorgBackend.get_from_name(&display.name).unwrap();
+                assert_eq!(rotated.rotation, 180.0);
+            }
+            Err(DIError::UnsupportedRotation(_)) => {}
+            Err(err) => panic!("set_rotation failed: {err}"),
+        }
+    }
+}
+
+#[test]
+#[ignore = "changes the configuration of the X server in DISPLAY"]
+fn set_rotation_rejects_invalid_rotation() {
+    let Some((_guard, displays)) = displays() else {
+        return;
+    };
+
+    for display in displays {
+        assert!(matches!(
+            XorgBackend.set_rotation(&display, 90.7, Reflection::default()),
+            Err(DIError::InvalidRotation(90.7))
+        ));
+    }
+}